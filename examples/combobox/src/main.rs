@@ -1,34 +1,42 @@
 use headlessui_sycamore::components::{
-    Combobox, ComboboxButton, ComboboxInput, ComboboxLabel, ComboboxOption, ComboboxOptions,
+    use_filtered_options, Combobox, ComboboxButton, ComboboxFilter, ComboboxInput, ComboboxLabel,
+    ComboboxOption, ComboboxOptionHighlight, ComboboxOptions,
 };
 use sycamore::prelude::*;
 
+#[component]
+fn Options<G: Html>(cx: Scope) -> View<G> {
+    let all_options = create_signal(cx, vec!["Hello", "World", "Test"]);
+    let options = use_filtered_options(cx, all_options, |opt| opt.to_string());
+
+    view! { cx,
+        Keyed(iterable = options, view = |cx, option| view! { cx,
+            ComboboxOption(value = option) {
+                ComboboxOptionHighlight(text = option)
+            }
+        }, key = |option| option.to_string())
+    }
+}
+
 #[component]
 fn App<G: Html>(cx: Scope) -> View<G> {
     let value = create_signal(cx, Some("Hello"));
-    let query = create_signal(cx, String::new());
 
-    let all_options = vec!["Hello", "World", "Test"];
-    let options = create_selector(cx, move || query.get()).map(cx, move |query| {
-        let query = query.to_lowercase();
-        all_options
-            .iter()
-            .filter(|opt| opt.to_lowercase().contains(&query))
-            .map(|v| *v)
-            .collect()
-    });
+    let display_value: Box<dyn Fn(&&str) -> String> = Box::new(|option| option.to_string());
 
     view! { cx,
-        Combobox(value = value) {
+        Combobox(
+            value = value,
+            display_value = display_value,
+            filter = ComboboxFilter::Fuzzy,
+        ) {
             ComboboxLabel { (value.get().unwrap()) }
-            ComboboxInput(bind:value = query)
+            ComboboxInput
             ComboboxButton {
                 "Open"
             }
             ComboboxOptions::<&str, _> {
-                Keyed(iterable = options, view = |cx, option| view! { cx,
-                    ComboboxOption(value = option) { (option) }
-                }, key = |option| option.to_string())
+                Options {}
             }
         }
     }
@@ -1,9 +1,13 @@
-use headlessui_sycamore::components::Transition;
+use headlessui_sycamore::components::{
+    SlideFrom, Transition, TransitionAnimation, TransitionGroup,
+};
 use sycamore::prelude::*;
 
 #[component]
 fn App<G: Html>(cx: Scope) -> View<G> {
     let show = create_signal(cx, false);
+    let items = create_signal(cx, vec![1, 2, 3]);
+    let next_item = create_signal(cx, 4);
 
     view! { cx,
         p { (format!("{}", show)) }
@@ -20,6 +24,29 @@ fn App<G: Html>(cx: Scope) -> View<G> {
         ) {
             "Hello World"
         }
+        Transition(show = show, animation = TransitionAnimation::slide_in(SlideFrom::Left)) {
+            "Hello Web Animations"
+        }
+        button(
+            on:click = |_| {
+                items.modify().insert(0, *next_item.get());
+                next_item.set(*next_item.get() + 1);
+            },
+            class = "p-1 m-1 border-gray-400 border",
+        ) { "Add" }
+        button(on:click = |_| { items.modify().pop(); }, class = "p-1 m-1 border-gray-400 border") {
+            "Remove"
+        }
+        button(on:click = |_| items.modify().reverse(), class = "p-1 m-1 border-gray-400 border") {
+            "Reverse"
+        }
+        TransitionGroup(
+            iterable = items,
+            view = |cx, item| view! { cx, div { (item) } },
+            key = |item| *item,
+            stagger = 50,
+            animation = TransitionAnimation::fade(),
+        )
     }
 }
 
@@ -0,0 +1,71 @@
+use super::ComboboxContext;
+use crate::{
+    components::DisclosureProperties,
+    utils::{class, SetDynAttr},
+};
+use sycamore::{builder::prelude::button, prelude::*, web::html::ev};
+use sycamore_utils::{DynamicElement, ReactiveBool, ReactiveStr};
+use web_sys::MouseEvent;
+
+#[derive(Props)]
+pub struct ComboboxClearProps<'cx, G: Html> {
+    #[prop(default, setter(into))]
+    disabled: ReactiveBool<'cx>,
+    #[prop(default, setter(into))]
+    class: ReactiveStr<'cx>,
+    #[prop(default = button.into(), setter(into))]
+    element: DynamicElement<'cx, G>,
+    children: Children<'cx, G>,
+    attributes: Attributes<'cx, G>,
+}
+
+#[component]
+pub fn ComboboxClear<'cx, G: Html>(cx: Scope<'cx>, props: ComboboxClearProps<'cx, G>) -> View<G> {
+    let context: &ComboboxContext = use_context(cx);
+    let properties: &DisclosureProperties = use_context(cx);
+
+    let on_click = {
+        let disabled = props.disabled.clone();
+        let properties_disabled = properties.disabled.clone();
+        move |_: MouseEvent| {
+            if !properties_disabled.get() && !disabled.get() {
+                context.clear();
+                if let Some(input) = context.input_element() {
+                    let _ = input.focus();
+                }
+            }
+        }
+    };
+    let disabled = create_memo(cx, move || {
+        properties.disabled.get() || props.disabled.get()
+    });
+    let class = class(cx, &props.attributes, props.class);
+    let children = props.children.call(cx);
+    props.attributes.exclude_keys(&[
+        "on:click",
+        "on:mousedown",
+        "class",
+        "type",
+        "aria-controls",
+        "disabled",
+        "data-sh",
+    ]);
+
+    let view = props.element.call(cx);
+    let element = view.as_node().unwrap();
+
+    element.set_dyn_attr(cx, "class", move || class.to_string());
+    element.set_children(cx, children);
+    element.apply_attributes(cx, &props.attributes);
+    element.set_attribute("data-sh".into(), "combobox-clear".into());
+
+    element.set_attribute("type".into(), "button".into());
+    element.set_attribute("aria-controls".into(), context.input_id.clone().into());
+    element.set_dyn_bool(cx, "disabled", move || *disabled.get());
+
+    element.event(cx, ev::click, on_click);
+    // Keeps focus in the input, so the list isn't closed by the click.
+    element.event(cx, ev::mousedown, |e: MouseEvent| e.prevent_default());
+
+    view
+}
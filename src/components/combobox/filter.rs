@@ -0,0 +1,257 @@
+use std::ops::Range;
+
+use super::ComboboxContext;
+use crate::utils::{class, SetDynAttr};
+use sycamore::{builder::prelude::span, prelude::*};
+use sycamore_utils::{DynamicElement, ReactiveStr};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ComboboxFilter {
+    #[default]
+    Contains,
+    StartsWith,
+    Fuzzy,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FilterMatch {
+    pub score: u32,
+    pub ranges: Vec<Range<usize>>,
+}
+
+const WORD_START_BONUS: u32 = 100;
+
+fn chars_eq(a: char, b: char) -> bool {
+    a == b || a.to_lowercase().eq(b.to_lowercase())
+}
+
+fn is_word_start(chars: &[(usize, char)], index: usize) -> bool {
+    index == 0 || !chars[index - 1].1.is_alphanumeric()
+}
+
+impl ComboboxFilter {
+    // Ranges are byte offsets into `text`, so they can be used to slice it directly.
+    pub fn matches(&self, text: &str, query: &str) -> Option<FilterMatch> {
+        if query.is_empty() {
+            return Some(FilterMatch::default());
+        }
+
+        let chars = text.char_indices().collect::<Vec<_>>();
+        let query = query.chars().collect::<Vec<_>>();
+        if query.len() > chars.len() {
+            return None;
+        }
+        let offset = |index: usize| chars.get(index).map_or(text.len(), |(offset, _)| *offset);
+        let matches_at = |start: usize| {
+            query
+                .iter()
+                .enumerate()
+                .all(|(i, q)| chars_eq(chars[start + i].1, *q))
+        };
+
+        match self {
+            ComboboxFilter::StartsWith => matches_at(0).then(|| FilterMatch {
+                score: u32::from(chars.len() == query.len()),
+                ranges: vec![0..offset(query.len())],
+            }),
+            ComboboxFilter::Contains => {
+                let last = chars.len() - query.len();
+                let start = (0..=last).find(|start| matches_at(*start))?;
+                // Earlier and word-aligned matches rank higher, the length of the text doesn't count.
+                let score = u32::MAX - WORD_START_BONUS - start as u32
+                    + if is_word_start(&chars, start) {
+                        WORD_START_BONUS
+                    } else {
+                        0
+                    };
+                Some(FilterMatch {
+                    score,
+                    ranges: vec![offset(start)..offset(start + query.len())],
+                })
+            }
+            ComboboxFilter::Fuzzy => {
+                let mut score = 0;
+                let mut ranges: Vec<Range<usize>> = Vec::new();
+                let mut query = query.iter().peekable();
+                let mut previous = None;
+
+                for (index, (byte, c)) in chars.iter().enumerate() {
+                    let Some(q) = query.peek() else {
+                        break;
+                    };
+                    if !chars_eq(*c, **q) {
+                        continue;
+                    }
+                    query.next();
+
+                    score += 1;
+                    if is_word_start(&chars, index) {
+                        score += 10;
+                    }
+                    let end = offset(index + 1);
+                    match ranges.last_mut() {
+                        Some(range) if previous == Some(index - 1) => {
+                            score += 5;
+                            range.end = end;
+                        }
+                        _ => ranges.push(*byte..end),
+                    }
+                    previous = Some(index);
+                }
+
+                query
+                    .peek()
+                    .is_none()
+                    .then_some(FilterMatch { score, ranges })
+            }
+        }
+    }
+}
+
+/// Filters and sorts `options` by the combobox query, using the `filter` strategy set on the
+/// surrounding [`Combobox`](super::Combobox).
+pub fn use_filtered_options<'cx, T, F>(
+    cx: Scope<'cx>,
+    options: &'cx ReadSignal<Vec<T>>,
+    text: F,
+) -> &'cx ReadSignal<Vec<T>>
+where
+    T: Clone,
+    F: Fn(&T) -> String + 'cx,
+{
+    let context: &ComboboxContext = use_context(cx);
+    create_memo(cx, move || {
+        let query = context.query.get();
+        let mut matches = options
+            .get()
+            .iter()
+            .filter_map(|option| {
+                context
+                    .filter
+                    .matches(&text(option), &query)
+                    .map(|found| (found.score, option.clone()))
+            })
+            .collect::<Vec<_>>();
+        // The sort is stable, so equally scored options keep their original order.
+        matches.sort_by(|(a, _), (b, _)| b.cmp(a));
+        matches.into_iter().map(|(_, option)| option).collect()
+    })
+}
+
+#[derive(Props)]
+pub struct ComboboxOptionHighlightProps<'cx, G: Html> {
+    #[prop(setter(into))]
+    text: String,
+    #[prop(default, setter(into))]
+    class: ReactiveStr<'cx>,
+    #[prop(default = span.into(), setter(into))]
+    element: DynamicElement<'cx, G>,
+    attributes: Attributes<'cx, G>,
+}
+
+#[component]
+pub fn ComboboxOptionHighlight<'cx, G: Html>(
+    cx: Scope<'cx>,
+    props: ComboboxOptionHighlightProps<'cx, G>,
+) -> View<G> {
+    let context: &ComboboxContext = use_context(cx);
+    let text = create_ref(cx, props.text);
+
+    let segments = create_memo(cx, move || {
+        let ranges = context
+            .filter
+            .matches(text, &context.query.get())
+            .map(|found| found.ranges)
+            .unwrap_or_default();
+
+        let mut segments = Vec::new();
+        let mut position = 0;
+        for range in ranges {
+            if range.start > position {
+                segments.push((text[position..range.start].to_string(), false));
+            }
+            segments.push((text[range.clone()].to_string(), true));
+            position = range.end;
+        }
+        if position < text.len() {
+            segments.push((text[position..].to_string(), false));
+        }
+        segments
+    });
+
+    let class = class(cx, &props.attributes, props.class);
+    props.attributes.exclude_keys(&["data-sh"]);
+
+    let view = props.element.call(cx);
+    let element = view.as_node().unwrap();
+
+    element.set_dyn_attr(cx, "class", move || class.to_string());
+    element.set_children(
+        cx,
+        view! { cx,
+            (View::new_fragment(
+                segments
+                    .get()
+                    .iter()
+                    .map(|(segment, matched)| {
+                        let segment = segment.clone();
+                        if *matched {
+                            view! { cx, mark { (segment) } }
+                        } else {
+                            view! { cx, (segment) }
+                        }
+                    })
+                    .collect(),
+            ))
+        },
+    );
+    element.apply_attributes(cx, &props.attributes);
+    element.set_attribute("data-sh".into(), "combobox-option-highlight".into());
+
+    view
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ComboboxFilter, FilterMatch};
+
+    #[test]
+    pub fn test_contains_is_case_insensitive() {
+        assert_eq!(
+            ComboboxFilter::Contains.matches("Hello World", "wor"),
+            Some(FilterMatch {
+                score: u32::MAX - 6,
+                ranges: vec![6..9]
+            })
+        );
+        assert_eq!(ComboboxFilter::Contains.matches("Hello", "xyz"), None);
+    }
+
+    #[test]
+    pub fn test_contains_ranks_by_match_position() {
+        let score = |text, query| ComboboxFilter::Contains.matches(text, query).unwrap().score;
+        assert!(score("cab", "ab") > score("zzzzzzzzzab", "ab"));
+        assert!(score("ab cd", "ab") > score("cab", "ab"));
+        assert_eq!(score("apple", "apple"), score("apple pie", "apple"));
+    }
+
+    #[test]
+    pub fn test_starts_with() {
+        assert!(ComboboxFilter::StartsWith.matches("Hello", "he").is_some());
+        assert!(ComboboxFilter::StartsWith.matches("Hello", "lo").is_none());
+    }
+
+    #[test]
+    pub fn test_fuzzy_merges_consecutive_ranges() {
+        let found = ComboboxFilter::Fuzzy.matches("New York", "nwyo").unwrap();
+        assert_eq!(found.ranges, vec![0..1, 2..3, 4..6]);
+        assert!(ComboboxFilter::Fuzzy.matches("New York", "yn").is_none());
+    }
+
+    #[test]
+    pub fn test_fuzzy_prefers_word_starts() {
+        let word_start = ComboboxFilter::Fuzzy.matches("big apple", "ap").unwrap();
+        let inner = ComboboxFilter::Fuzzy.matches("grape", "ap").unwrap();
+        assert!(word_start.score > inner.score);
+    }
+}
@@ -0,0 +1,33 @@
+use sycamore::prelude::*;
+
+use super::ComboboxContext;
+use crate::components::select::{
+    select_group, select_group_label, select_separator, SelectGroupLabelProps, SelectGroupProps,
+    SelectSeparatorProps,
+};
+
+pub type ComboboxGroupProps<'cx, G> = SelectGroupProps<'cx, G>;
+pub type ComboboxGroupLabelProps<'cx, G> = SelectGroupLabelProps<'cx, G>;
+pub type ComboboxSeparatorProps<'cx, G> = SelectSeparatorProps<'cx, G>;
+
+#[component]
+pub fn ComboboxGroup<'cx, G: Html>(cx: Scope<'cx>, props: ComboboxGroupProps<'cx, G>) -> View<G> {
+    select_group(cx, props, "combobox")
+}
+
+#[component]
+pub fn ComboboxGroupLabel<'cx, G: Html>(
+    cx: Scope<'cx>,
+    props: ComboboxGroupLabelProps<'cx, G>,
+) -> View<G> {
+    select_group_label(cx, props, "combobox")
+}
+
+#[component]
+pub fn ComboboxSeparator<'cx, G: Html>(
+    cx: Scope<'cx>,
+    props: ComboboxSeparatorProps<'cx, G>,
+) -> View<G> {
+    let context: &ComboboxContext = use_context(cx);
+    select_separator(cx, props, "combobox", context.horizontal)
+}
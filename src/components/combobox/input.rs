@@ -1,4 +1,4 @@
-use super::ComboboxContext;
+use super::{ActiveOption, ComboboxContext, ComboboxFreeText};
 use crate::{
     components::DisclosureProperties,
     utils::{class, get_ref, SetDynAttr},
@@ -6,10 +6,65 @@ use crate::{
 use sycamore::{builder::prelude::input, prelude::*, web::html::ev};
 use sycamore_utils::{DynamicElement, ReactiveBool, ReactiveStr};
 use wasm_bindgen::JsCast;
-use web_sys::{HtmlElement, KeyboardEvent};
+use web_sys::{Event, FocusEvent, HtmlElement, HtmlInputElement, InputEvent, KeyboardEvent};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ComboboxAutocomplete {
+    #[default]
+    List,
+    Inline,
+    Both,
+}
+
+impl ComboboxAutocomplete {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ComboboxAutocomplete::List => "list",
+            ComboboxAutocomplete::Inline => "inline",
+            ComboboxAutocomplete::Both => "both",
+        }
+    }
+}
+
+// Extends the typed text with the rest of the first option starting with it and selects the
+// added suffix, so typing on simply replaces it.
+fn complete_inline(context: &ComboboxContext, input: &HtmlInputElement) {
+    let typed = input.value();
+    if typed.is_empty() {
+        return;
+    }
+    let Some(list) = context.options_element() else {
+        return;
+    };
+    let typed_lower = typed.to_lowercase();
+    let found = context
+        .enabled_options(&list)
+        .into_iter()
+        .filter(|option| !option.has_attribute("data-sh-create"))
+        .find_map(|option| {
+            let text = option.text_content()?.trim().to_string();
+            text.to_lowercase()
+                .starts_with(&typed_lower)
+                .then_some((option, text))
+        });
+    let Some((option, text)) = found else {
+        return;
+    };
+
+    let suffix = text.chars().skip(typed.chars().count()).collect::<String>();
+    if !suffix.is_empty() {
+        input.set_value(&format!("{typed}{suffix}"));
+        let start = typed.encode_utf16().count() as u32;
+        let end = start + suffix.encode_utf16().count() as u32;
+        let _ = input.set_selection_range(start, end);
+    }
+    context.active_id.set(Some(option.id()));
+}
 
 #[derive(Props)]
 pub struct ComboboxInputProps<'cx, G: Html> {
+    #[prop(default)]
+    autocomplete: ComboboxAutocomplete,
     #[prop(default, setter(into))]
     disabled: ReactiveBool<'cx>,
     #[prop(default, setter(into))]
@@ -25,6 +80,35 @@ pub fn ComboboxInput<'cx, G: Html>(cx: Scope<'cx>, props: ComboboxInputProps<'cx
     let properties: &DisclosureProperties = use_context(cx);
 
     let node = get_ref(cx, &props.attributes);
+    let input_element = move || {
+        node.try_get::<DomNode>()
+            .and_then(|node| node.to_web_sys().dyn_into::<HtmlInputElement>().ok())
+    };
+
+    let sync_text = move || {
+        if let Some(text) = context.display_text.get_untracked().as_ref() {
+            if let Some(input) = input_element() {
+                input.set_value(text);
+            }
+            context.query.set(String::new());
+        }
+    };
+    // Kept free text must survive closing the list.
+    let revert_text = move || {
+        if context.free_text != ComboboxFreeText::Keep {
+            sync_text();
+        }
+    };
+
+    create_effect(cx, move || {
+        context.display_text.track();
+        sync_text();
+    });
+    create_effect(cx, move || {
+        if !*properties.open.get() {
+            revert_text();
+        }
+    });
 
     create_effect(cx, {
         let id = context.options_id.clone();
@@ -39,21 +123,177 @@ pub fn ComboboxInput<'cx, G: Html>(cx: Scope<'cx>, props: ComboboxInputProps<'cx
         }
     });
 
+    create_effect(cx, move || {
+        if let Some(node) = node.try_get_raw() {
+            match context.active_id.get().as_ref() {
+                Some(id) if *properties.open.get() => {
+                    node.set_attribute("aria-activedescendant".into(), id.clone().into());
+                }
+                _ => node.remove_attribute("aria-activedescendant".into()),
+            }
+        }
+    });
+
     let on_key_down = {
         let disabled = props.disabled.clone();
         let properties_disabled = properties.disabled.clone();
         move |e: KeyboardEvent| {
-            if !properties_disabled.get() && !disabled.get() {
-                match e.key().as_str() {
-                    "ArrowUp" | "ArrowDown" => {
+            if properties_disabled.get() || disabled.get() {
+                return;
+            }
+            // Keys confirming or navigating an IME candidate belong to the composition.
+            if *context.composing.get_untracked() || e.is_composing() || e.key_code() == 229 {
+                return;
+            }
+            let open = *properties.open.get_untracked();
+            let at_start = input_element().map_or(false, |input| {
+                input.selection_start() == Ok(Some(0)) && input.selection_end() == Ok(Some(0))
+            });
+            let active_chip = (*context.active_chip.get_untracked()).clone();
+            match e.key().as_str() {
+                "Backspace" | "Delete" if context.multiple && active_chip.is_some() => {
+                    e.prevent_default();
+                    context.active_chip.set(None);
+                    context.remove_chip(&active_chip.unwrap());
+                }
+                // The last chip is highlighted first, so it isn't removed by accident.
+                "Backspace"
+                    if context.multiple
+                        && input_element().map_or(false, |input| input.value().is_empty()) =>
+                {
+                    if context.move_active_chip(false) {
                         e.prevent_default();
-                        properties.open.set(!*properties.open.get_untracked());
                     }
-                    _ => {}
                 }
+                key @ ("ArrowLeft" | "ArrowRight")
+                    if context.multiple && (at_start || active_chip.is_some()) =>
+                {
+                    if context.move_active_chip(key == "ArrowRight") {
+                        e.prevent_default();
+                    }
+                }
+                "ArrowUp" | "ArrowDown" if !open => {
+                    e.prevent_default();
+                    properties.open.set(true);
+                }
+                // Only the arrows along the list's orientation move the active option.
+                key @ ("ArrowUp" | "ArrowDown" | "ArrowLeft" | "ArrowRight") if open => {
+                    let target = match (key, context.horizontal) {
+                        ("ArrowUp", false) | ("ArrowLeft", true) => ActiveOption::Prev,
+                        ("ArrowDown", false) | ("ArrowRight", true) => ActiveOption::Next,
+                        _ => return,
+                    };
+                    e.prevent_default();
+                    if let Some(list) = context.options_element() {
+                        context.set_active_option(&list, target);
+                    }
+                }
+                key @ ("Home" | "End") if open => {
+                    e.prevent_default();
+                    if let Some(list) = context.options_element() {
+                        let target = if key == "Home" {
+                            ActiveOption::First
+                        } else {
+                            ActiveOption::Last
+                        };
+                        context.set_active_option(&list, target);
+                    }
+                }
+                "Enter" => {
+                    if let Some(option) = open.then(|| context.active_option()).flatten() {
+                        e.prevent_default();
+                        option.click();
+                        return;
+                    }
+                    let query = context.query.get_untracked();
+                    if query.trim().is_empty() {
+                        return;
+                    }
+                    match context.create.as_ref() {
+                        Some(create) if context.free_text == ComboboxFreeText::Create => {
+                            e.prevent_default();
+                            create((*query).clone());
+                        }
+                        // Closing keeps or reverts the raw text, depending on `free_text`.
+                        _ => properties.open.set(false),
+                    }
+                }
+                "Escape" => {
+                    e.prevent_default();
+                    let empty = input_element().map_or(false, |input| input.value().is_empty());
+                    if context.nullable && empty {
+                        context.clear();
+                    }
+                    if open {
+                        properties.open.set(false);
+                    } else if let Some(input) = input_element() {
+                        input.set_value("");
+                        if let Ok(event) = Event::new("input") {
+                            let _ = input.dispatch_event(&event);
+                        }
+                    }
+                }
+                _ => context.active_chip.set(None),
             }
         }
     };
+    let update_query = create_ref(cx, {
+        let disabled = props.disabled.clone();
+        let properties_disabled = properties.disabled.clone();
+        move |inserted: bool| {
+            if let Some(input) = input_element() {
+                let text = input.value();
+                if context.nullable && !context.multiple && text.is_empty() {
+                    (context.clear_value)();
+                }
+                context.query.set(text);
+            }
+            if properties_disabled.get() || disabled.get() {
+                return;
+            }
+            // The filtered list has already been updated by the time this runs.
+            if !*properties.open.get_untracked() {
+                properties.open.set(true);
+            } else if let Some(list) = context.options_element() {
+                context.reconcile_active_option(&list);
+            }
+            if inserted && props.autocomplete != ComboboxAutocomplete::List {
+                if let Some(input) = input_element() {
+                    complete_inline(context, &input);
+                }
+            }
+        }
+    });
+    // Intermediate composition strings would make the list flicker, so the query only follows
+    // the committed text.
+    let on_input = move |e: Event| {
+        let composing = e
+            .dyn_ref::<InputEvent>()
+            .map_or(false, |e| e.is_composing());
+        if composing || *context.composing.get_untracked() {
+            return;
+        }
+        // Deleted text must not be completed again right away.
+        let inserted = e
+            .dyn_ref::<InputEvent>()
+            .map_or(false, |e| !e.input_type().starts_with("delete"));
+        update_query(inserted);
+    };
+    let on_composition_end = move |_| {
+        context.composing.set(false);
+        update_query(false);
+    };
+    let on_blur = move |_: FocusEvent| {
+        context.active_chip.set(None);
+        if *context.hovering.get_untracked() {
+            return;
+        }
+        if *properties.open.get_untracked() {
+            properties.open.set(false);
+        } else {
+            revert_text();
+        }
+    };
     let on_click = {
         let disabled = props.disabled.clone();
         let properties_disabled = properties.disabled.clone();
@@ -74,7 +314,13 @@ pub fn ComboboxInput<'cx, G: Html>(cx: Scope<'cx>, props: ComboboxInputProps<'cx
     props.attributes.exclude_keys(&[
         "on:keydown",
         "on:click",
+        "on:focusout",
+        "on:compositionstart",
+        "on:compositionend",
         "id",
+        "role",
+        "aria-activedescendant",
+        "aria-autocomplete",
         "class",
         "on:mouseenter",
         "on:mouseleave",
@@ -96,13 +342,24 @@ pub fn ComboboxInput<'cx, G: Html>(cx: Scope<'cx>, props: ComboboxInputProps<'cx
     element.set_attribute("data-sh".into(), "combobox-input".into());
 
     element.set_attribute("id".into(), context.input_id.clone().into());
+    element.set_attribute("role".into(), "combobox".into());
     element.set_attribute("aria-haspopup".into(), "listbox".into());
+    element.set_attribute(
+        "aria-autocomplete".into(),
+        props.autocomplete.as_str().into(),
+    );
     element.set_dyn_bool(cx, "disabled", move || *disabled.get());
     element.set_dyn_bool(cx, "aria-expanded", move || *properties.open.get());
     element.set_dyn_bool(cx, "data-sh-expanded", move || *properties.open.get());
 
     element.event(cx, ev::keydown, on_key_down);
     element.event(cx, ev::click, on_click);
+    element.event(cx, ev::input, on_input);
+    element.event(cx, ev::compositionstart, move |_| {
+        context.composing.set(true)
+    });
+    element.event(cx, ev::compositionend, on_composition_end);
+    element.event(cx, ev::focusout, on_blur);
     element.event(cx, ev::mouseenter, move |_| context.hovering.set(true));
     element.event(cx, ev::mouseleave, move |_| context.hovering.set(false));
 
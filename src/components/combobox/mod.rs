@@ -1,31 +1,63 @@
-use std::{collections::HashSet, hash::Hash, mem, rc::Rc};
+use std::{cell::RefCell, collections::HashMap, mem, rc::Rc};
 
 use sycamore::{builder::prelude::div, prelude::*};
 use sycamore_utils::{DynamicElement, ReactiveBool, ReactiveStr};
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlElement, HtmlInputElement, ScrollIntoViewOptions, ScrollLogicalPosition};
 
 use crate::{
     hooks::create_id,
-    utils::{class, get_ref, scoped_children, FocusStartPoint, SetDynAttr},
-    FocusNavigator,
+    utils::{as_static, class, get_ref, scoped_children, FocusStartPoint, SetDynAttr},
 };
 
 mod button;
+mod clear;
+mod filter;
+mod group;
 mod input;
 mod label;
 mod options;
+mod selection;
+mod source;
+mod status;
 
 pub use button::*;
+pub use clear::*;
+pub use filter::*;
+pub use group::*;
 pub use input::*;
 pub use label::*;
 pub use options::*;
+pub use selection::*;
+pub use source::*;
+pub use status::*;
 
-use super::{DisclosureProperties, SelectProperties, SelectValue};
+use super::{
+    provide_select_validity, use_headless_select, DisclosureProperties, HeadlessSelectOptions,
+    SelectBy, SelectSignal, SelectValue, VirtualListSlot,
+};
 
 #[derive(Props)]
-pub struct ComboboxProps<'cx, T: Clone + Eq + Hash + 'static, G: Html> {
-    value: Option<&'cx Signal<Option<T>>>,
-    value_multiple: Option<&'cx Signal<HashSet<T>>>,
+pub struct ComboboxProps<'cx, T: Clone + 'static, G: Html> {
+    #[prop(setter(into))]
+    value: Option<SelectSignal<'cx, Option<T>, T>>,
+    /// Selected values, kept in the order they were picked in.
+    #[prop(setter(into))]
+    value_multiple: Option<SelectSignal<'cx, Vec<T>, T>>,
     open: Option<&'cx Signal<bool>>,
+    query: Option<&'cx Signal<String>>,
+    /// Text shown in the input for the selected value, the typed text is kept without it.
+    #[prop(setter(into))]
+    display_value: Option<Box<dyn Fn(&T) -> String + 'cx>>,
+    #[prop(default)]
+    filter: ComboboxFilter,
+    async_options: Option<AsyncOptions<'cx, T>>,
+    #[prop(setter(into))]
+    on_create: Option<Box<dyn Fn(String) -> Option<T> + 'cx>>,
+    #[prop(default)]
+    free_text: ComboboxFreeText,
+    #[prop(default)]
+    nullable: bool,
     #[prop(default)]
     default_open: bool,
     #[prop(default)]
@@ -37,6 +69,13 @@ pub struct ComboboxProps<'cx, T: Clone + Eq + Hash + 'static, G: Html> {
     #[prop(default)]
     toggleable: bool,
     #[prop(default, setter(into))]
+    by: SelectBy<T>,
+    #[prop(default)]
+    required: bool,
+    min_selected: Option<usize>,
+    max_selected: Option<usize>,
+    valid: Option<&'cx Signal<bool>>,
+    #[prop(default, setter(into))]
     class: ReactiveStr<'cx>,
     #[prop(default = div.into(), setter(into))]
     element: DynamicElement<'cx, G>,
@@ -44,6 +83,13 @@ pub struct ComboboxProps<'cx, T: Clone + Eq + Hash + 'static, G: Html> {
     attributes: Attributes<'cx, G>,
 }
 
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ComboboxFreeText {
+    #[default]
+    Create,
+    Keep,
+}
+
 pub struct ComboboxContext {
     multiple: bool,
     owner_id: String,
@@ -53,10 +99,188 @@ pub struct ComboboxContext {
     options_id: String,
     horizontal: bool,
     hovering: &'static Signal<bool>,
+    composing: &'static Signal<bool>,
+    active_id: &'static Signal<Option<String>>,
+    query: &'static Signal<String>,
+    display_text: &'static ReadSignal<Option<String>>,
+    filter: ComboboxFilter,
+    loading: &'static ReadSignal<bool>,
+    error: &'static ReadSignal<Option<String>>,
+    empty: &'static ReadSignal<bool>,
+    option_count: &'static Signal<usize>,
+    create: Option<Box<dyn Fn(String)>>,
+    free_text: ComboboxFreeText,
+    nullable: bool,
+    clear_value: Box<dyn Fn()>,
+    active_chip: &'static Signal<Option<String>>,
+    chips: RefCell<HashMap<String, Rc<dyn Fn()>>>,
+    virtual_list: VirtualListSlot,
+}
+
+#[derive(Clone, Copy)]
+enum ActiveOption {
+    Selected,
+    Next,
+    Prev,
+    First,
+    Last,
+}
+
+// Focus stays in the input, options are only marked active through `aria-activedescendant`.
+impl ComboboxContext {
+    fn options_element(&self) -> Option<HtmlElement> {
+        web_sys::window()?
+            .document()?
+            .get_element_by_id(&self.options_id)?
+            .dyn_into()
+            .ok()
+    }
+
+    fn input_element(&self) -> Option<HtmlInputElement> {
+        web_sys::window()?
+            .document()?
+            .get_element_by_id(&self.input_id)?
+            .dyn_into()
+            .ok()
+    }
+
+    fn clear(&self) {
+        (self.clear_value)();
+        self.query.set(String::new());
+        if let Some(input) = self.input_element() {
+            input.set_value("");
+        }
+    }
+
+    fn active_option(&self) -> Option<HtmlElement> {
+        let id = self.active_id.get_untracked();
+        web_sys::window()?
+            .document()?
+            .get_element_by_id(id.as_ref().as_ref()?)?
+            .dyn_into()
+            .ok()
+    }
+
+    fn enabled_options(&self, list: &HtmlElement) -> Vec<HtmlElement> {
+        let Ok(nodes) = list.query_selector_all(&format!(
+            "[role=\"option\"][data-sh-owner=\"{}\"]",
+            self.owner_id
+        )) else {
+            return Vec::new();
+        };
+        (0..nodes.length())
+            .filter_map(|i| nodes.get(i)?.dyn_into::<HtmlElement>().ok())
+            .filter(|option| option.get_attribute("data-sh-disabled").as_deref() != Some("true"))
+            .collect()
+    }
+
+    fn set_active_option(&self, list: &HtmlElement, target: ActiveOption) {
+        if let Some(virtual_list) = self.virtual_list.get().filter(|list| !list.is_empty()) {
+            let current = self
+                .active_option()
+                .and_then(|option| virtual_list.index_of(list, &self.owner_id, &option));
+            let index = match target {
+                ActiveOption::Next => virtual_list.step(current, true, true),
+                ActiveOption::Prev => virtual_list.step(current, false, true),
+                ActiveOption::First => virtual_list.step(None, true, false),
+                ActiveOption::Last => virtual_list.step(None, false, false),
+                ActiveOption::Selected => virtual_list
+                    .selected()
+                    .or_else(|| virtual_list.step(None, true, false)),
+            };
+            let next = index.and_then(|index| virtual_list.reveal(list, &self.owner_id, index));
+            self.active_id.set(next.map(|option| option.id()));
+            return;
+        }
+
+        let options = self.enabled_options(list);
+        let active_id = self.active_id.get_untracked();
+        let current = active_id
+            .as_ref()
+            .as_ref()
+            .and_then(|id| options.iter().position(|option| &option.id() == id));
+
+        let next = match (target, current) {
+            (ActiveOption::Next, Some(i)) => options.get((i + 1) % options.len()),
+            (ActiveOption::Next, None) => options.first(),
+            (ActiveOption::Prev, Some(i)) => options.get((i + options.len() - 1) % options.len()),
+            (ActiveOption::Prev, None) | (ActiveOption::Last, _) => options.last(),
+            (ActiveOption::First, _) => options.first(),
+            (ActiveOption::Selected, _) => options
+                .iter()
+                .find(|option| option.has_attribute("aria-selected"))
+                .or_else(|| {
+                    // Free text is kept on Enter, so creating is only done explicitly.
+                    options.iter().find(|option| {
+                        self.free_text == ComboboxFreeText::Create
+                            || !option.has_attribute("data-sh-create")
+                    })
+                }),
+        };
+
+        if let Some(next) = next {
+            let mut scroll_options = ScrollIntoViewOptions::new();
+            scroll_options.block(ScrollLogicalPosition::Nearest);
+            next.scroll_into_view_with_scroll_into_view_options(&scroll_options);
+        }
+        self.active_id.set(next.map(|option| option.id()));
+    }
+
+    fn chip_elements(&self) -> Vec<HtmlElement> {
+        let Some(document) = web_sys::window().and_then(|window| window.document()) else {
+            return Vec::new();
+        };
+        let Ok(nodes) = document.query_selector_all(&format!(
+            "[data-sh=\"combobox-chip\"][data-sh-owner=\"{}\"]",
+            self.owner_id
+        )) else {
+            return Vec::new();
+        };
+        (0..nodes.length())
+            .filter_map(|i| nodes.get(i)?.dyn_into::<HtmlElement>().ok())
+            .collect()
+    }
+
+    // Moving right past the last chip hands the highlight back to the input.
+    fn move_active_chip(&self, forward: bool) -> bool {
+        let chips = self.chip_elements();
+        let active_id = self.active_chip.get_untracked();
+        let position = active_id
+            .as_ref()
+            .as_ref()
+            .and_then(|id| chips.iter().position(|chip| &chip.id() == id));
+        let next = match (position, forward) {
+            (None, false) => chips.len().checked_sub(1),
+            (None, true) => return false,
+            (Some(i), false) => Some(i.saturating_sub(1)),
+            (Some(i), true) => (i + 1 < chips.len()).then_some(i + 1),
+        };
+        self.active_chip.set(next.map(|i| chips[i].id()));
+        true
+    }
+
+    fn remove_chip(&self, id: &str) {
+        let remove = self.chips.borrow().get(id).cloned();
+        if let Some(remove) = remove {
+            remove();
+        }
+    }
+
+    fn reconcile_active_option(&self, list: &HtmlElement) {
+        let active_id = self.active_id.get_untracked();
+        let still_present = active_id.as_ref().as_ref().map_or(false, |id| {
+            self.enabled_options(list)
+                .iter()
+                .any(|option| &option.id() == id)
+        });
+        if !still_present {
+            self.set_active_option(list, ActiveOption::Selected);
+        }
+    }
 }
 
 #[component]
-pub fn Combobox<'cx, T: Clone + Eq + Hash + 'static, G: Html>(
+pub fn Combobox<'cx, T: Clone + 'static, G: Html>(
     cx: Scope<'cx>,
     props: ComboboxProps<'cx, T, G>,
 ) -> View<G> {
@@ -65,11 +289,33 @@ pub fn Combobox<'cx, T: Clone + Eq + Hash + 'static, G: Html>(
         .unwrap_or_else(|| create_signal(cx, props.default_open));
 
     let hovering = create_signal(cx, false);
+    let composing = create_signal(cx, false);
+    let active_chip = create_signal(cx, None::<String>);
+    let active_id = create_signal(cx, None::<String>);
+    let query = props
+        .query
+        .unwrap_or_else(|| create_signal(cx, String::new()));
     let owner_id = create_id();
     let label_id = create_id();
 
+    let (loading, error, empty): (
+        &ReadSignal<bool>,
+        &ReadSignal<Option<String>>,
+        &ReadSignal<bool>,
+    ) = match props.async_options {
+        Some(source) => (source.loading, source.error, source.empty),
+        None => (
+            create_signal(cx, false),
+            create_signal(cx, None),
+            create_signal(cx, false),
+        ),
+    };
+
+    let option_count = create_signal(cx, 0);
+    let display_text = create_signal(cx, None::<String>);
+
     let focus_start = FocusStartPoint::new(cx);
-    let context = ComboboxContext {
+    let mut context = ComboboxContext {
         multiple: props.value_multiple.is_some(),
         owner_id: owner_id.clone(),
         label_id: label_id.clone(),
@@ -78,37 +324,114 @@ pub fn Combobox<'cx, T: Clone + Eq + Hash + 'static, G: Html>(
         input_id: create_id(),
         horizontal: props.horizontal,
         hovering: unsafe { mem::transmute(hovering) },
+        composing: unsafe { mem::transmute(composing) },
+        active_id: unsafe { mem::transmute(active_id) },
+        query: unsafe { mem::transmute(query) },
+        display_text: unsafe { mem::transmute(display_text) },
+        filter: props.filter,
+        loading: unsafe { mem::transmute(loading) },
+        error: unsafe { mem::transmute(error) },
+        empty: unsafe { mem::transmute(empty) },
+        option_count: unsafe { mem::transmute(option_count) },
+        create: None,
+        free_text: props.free_text,
+        nullable: props.nullable,
+        clear_value: Box::new(|| {}),
+        active_chip: unsafe { mem::transmute(active_chip) },
+        chips: RefCell::new(HashMap::new()),
+        virtual_list: VirtualListSlot::default(),
     };
 
-    let active = create_signal::<Option<Rc<T>>>(cx, None);
     let disclosure_properties = DisclosureProperties {
         open: unsafe { mem::transmute(open) },
         disabled: unsafe { mem::transmute(props.disabled.clone()) },
     };
-    let properties = if let Some(value) = props.value {
-        SelectProperties::<T> {
-            value: SelectValue::Single(unsafe { mem::transmute(value) }),
-            active: unsafe { mem::transmute(active) },
-            disabled: unsafe { mem::transmute(props.disabled.clone()) },
-            toggleable: props.toggleable,
-        }
+    let (value, by) = if let Some(value) = props.value {
+        let (value, by) = value.into_parts();
+        (SelectValue::Single(as_static(value)), by)
     } else if let Some(value) = props.value_multiple {
-        SelectProperties::<T> {
-            value: SelectValue::Multiple(unsafe { mem::transmute(value) }),
-            active: unsafe { mem::transmute(active) },
-            disabled: unsafe { mem::transmute(props.disabled.clone()) },
-            toggleable: props.toggleable,
-        }
+        let (value, by) = value.into_parts();
+        (SelectValue::Multiple(as_static(value)), by)
     } else {
         return view! { cx, span { "Must provide either 'value' or 'value_multiple'." } };
     };
+    let by = props.by.or(by);
+    if !by.is_set() {
+        return view! { cx, span { "Must provide 'by' for values without a 'SelectKey'." } };
+    }
+    let properties = use_headless_select(
+        cx,
+        HeadlessSelectOptions {
+            value,
+            disabled: unsafe { mem::transmute(props.disabled.clone()) },
+            toggleable: props.toggleable,
+            by,
+            required: props.required,
+            min_selected: props.min_selected,
+            max_selected: props.max_selected,
+        },
+    );
+    let active = properties.active;
+    create_effect(cx, move || {
+        if active_id.get().is_none() {
+            active.set(None);
+        }
+    });
+    // Multiple selections are shown elsewhere, so the input is emptied for the next pick.
+    let display_value = props.display_value;
+    let text = create_memo(cx, {
+        let properties = properties.clone();
+        move || match &properties.value {
+            SelectValue::Single(selected) => display_value.as_ref().map(|display| {
+                selected
+                    .get()
+                    .as_ref()
+                    .as_ref()
+                    .map(|value| display(value))
+                    .unwrap_or_default()
+            }),
+            SelectValue::Multiple(selected) => {
+                selected.track();
+                Some(String::new())
+            }
+        }
+    });
+    context.display_text = unsafe { mem::transmute(text) };
+    let valid = properties.valid();
+    create_effect(cx, move || {
+        if let Some(signal) = props.valid {
+            signal.set(*valid.get());
+        }
+    });
+
+    let clear_value: Box<dyn Fn() + 'cx> = Box::new({
+        let properties = properties.clone();
+        move || properties.clear()
+    });
+    context.clear_value = unsafe { mem::transmute(clear_value) };
+
+    if let Some(on_create) = props.on_create {
+        let properties = properties.clone();
+        let multiple = context.multiple;
+        let create: Box<dyn Fn(String) + 'cx> = Box::new(move |query| {
+            if let Some(value) = on_create(query) {
+                properties.select(value);
+                if !multiple {
+                    open.set(false);
+                }
+            }
+        });
+        context.create = Some(unsafe { mem::transmute(create) });
+    }
 
     let node_ref = get_ref(cx, &props.attributes);
+    let virtual_list = context.virtual_list.clone();
     let children = scoped_children(cx, props.children, |cx| {
         provide_context(cx, context);
+        provide_context(cx, virtual_list);
+        provide_select_validity(cx, &properties);
         provide_context(cx, properties);
         provide_context(cx, disclosure_properties);
-        provide_context(cx, FocusNavigator::new(owner_id.clone(), node_ref));
     });
 
     create_effect(cx, move || {
@@ -124,9 +447,13 @@ pub fn Combobox<'cx, T: Clone + Eq + Hash + 'static, G: Html>(
     });
     let class = class(cx, &props.attributes, props.class);
 
-    props
-        .attributes
-        .exclude_keys(&["data-sh", "id", "aria-labelledby", "disabled"]);
+    props.attributes.exclude_keys(&[
+        "data-sh",
+        "id",
+        "aria-labelledby",
+        "disabled",
+        "data-sh-invalid",
+    ]);
 
     let view = props.element.call(cx);
     let element = view.as_node().unwrap();
@@ -141,6 +468,7 @@ pub fn Combobox<'cx, T: Clone + Eq + Hash + 'static, G: Html>(
     element.set_attribute("id".into(), owner_id.into());
     element.set_attribute("aria-labelledby".into(), label_id.into());
     element.set_dyn_bool(cx, "disabled", move || props.disabled.get());
+    element.set_dyn_bool(cx, "data-sh-invalid", move || !*valid.get());
 
     view
     /* view! {cx,
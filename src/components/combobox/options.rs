@@ -1,20 +1,24 @@
-use super::ComboboxContext;
+use super::{ActiveOption, ComboboxContext};
 use crate::{
     components::{
-        select::SelectProperties, DisclosureProperties, TransitionContext, TransitionProp,
+        select::{count_group_option, SelectProperties},
+        transition_children, DisclosureProperties, PanelTransitionContext, TransitionContext,
+        TransitionProp,
+    },
+    hooks::create_id,
+    utils::{
+        as_static, class, focus_navigation::as_html_element, get_ref, load_more_sentinel,
+        scoped_children, OptionText, SetDynAttr,
     },
-    utils::{as_static, class, get_ref, SetDynAttr},
-    FocusNavigator,
 };
-use std::hash::Hash;
 use sycamore::{
-    builder::prelude::{li, ul},
+    builder::prelude::{div, li, ul},
     prelude::*,
     rt::JsCast,
     web::html::ev,
 };
 use sycamore_utils::{DynamicElement, ReactiveBool, ReactiveStr};
-use web_sys::{FocusEvent, HtmlElement, KeyboardEvent};
+use web_sys::{FocusEvent, HtmlElement, MouseEvent};
 
 #[derive(Props)]
 pub struct ComboboxOptionsProps<'cx, G: Html> {
@@ -23,6 +27,17 @@ pub struct ComboboxOptionsProps<'cx, G: Html> {
     #[prop(default, setter(into))]
     class: ReactiveStr<'cx>,
     transition: Option<TransitionProp<'cx, G>>,
+    #[prop(default = true)]
+    unmount: bool,
+    #[prop(setter(into))]
+    on_load_more: Option<Box<dyn Fn() + 'cx>>,
+    #[prop(default, setter(into))]
+    has_more: ReactiveBool<'cx>,
+    #[prop(default, setter(into))]
+    loading_more: ReactiveBool<'cx>,
+    /// Text of the option creating a value from the query, `Create "<query>"` by default.
+    #[prop(setter(into))]
+    create_label: Option<Box<dyn Fn(&str) -> String + 'cx>>,
     #[prop(default = ul.into(), setter(into))]
     element: DynamicElement<'cx, G>,
     children: Children<'cx, G>,
@@ -30,20 +45,31 @@ pub struct ComboboxOptionsProps<'cx, G: Html> {
 }
 
 #[component]
-pub fn ComboboxOptions<'cx, T: Clone + Hash + Eq + 'static, G: Html>(
+pub fn ComboboxOptions<'cx, T: Clone + 'static, G: Html>(
     cx: Scope<'cx>,
     props: ComboboxOptionsProps<'cx, G>,
 ) -> View<G> {
     let context: &ComboboxContext = use_context(cx);
-    let select_properties: &SelectProperties<T> = use_context(cx);
     let properties: &DisclosureProperties = use_context(cx);
 
     let internal_ref = get_ref(cx, &props.attributes);
-    let controller: &FocusNavigator<'static, G> = use_context(cx);
 
     create_effect(cx, move || {
-        if !select_properties.has_selected() {
-            controller.set_first_checked();
+        if *properties.open.get() {
+            if let Some(list) = as_html_element(internal_ref) {
+                context.set_active_option(&list, ActiveOption::Selected);
+            }
+        } else {
+            context.active_id.set(None);
+        }
+    });
+
+    // Freshly loaded options replace the ones the active option pointed at.
+    create_effect(cx, move || {
+        if !*context.loading.get() && *properties.open.get_untracked() {
+            if let Some(list) = as_html_element(internal_ref) {
+                context.reconcile_active_option(&list);
+            }
         }
     });
 
@@ -64,7 +90,17 @@ pub fn ComboboxOptions<'cx, T: Clone + Hash + Eq + 'static, G: Html>(
     });
     let tabindex = create_memo(cx, move || if *disabled.get() { -1 } else { 0 });
     let class = class(cx, &props.attributes, props.class);
-    let children = props.children.call(cx);
+    let (children, transition_state) =
+        transition_children(cx, props.children, props.transition.is_some());
+    let loading_more = props.loading_more.clone();
+    let load_more = load_more_sentinel(
+        cx,
+        "combobox-load-more",
+        props.on_load_more,
+        props.has_more,
+        props.loading_more,
+    );
+    let create_option = create_option_view(cx, context, props.create_label);
 
     props.attributes.exclude_keys(&[
         "on:focusout",
@@ -74,6 +110,7 @@ pub fn ComboboxOptions<'cx, T: Clone + Hash + Eq + 'static, G: Html>(
         "aria-multiselectable",
         "aria-labelledby",
         "aria-orientation",
+        "aria-busy",
         "tabindex",
         "disabled",
     ]);
@@ -82,7 +119,7 @@ pub fn ComboboxOptions<'cx, T: Clone + Hash + Eq + 'static, G: Html>(
         internal_ref.set(element.clone());
 
         element.set_dyn_attr(cx, "class", move || class.to_string());
-        element.set_children(cx, view! { cx, (children) });
+        element.set_children(cx, view! { cx, (children) (create_option) (load_more) });
         element.apply_attributes(cx, &props.attributes);
         element.set_attribute("data-sh".into(), "combobox-options".into());
 
@@ -92,6 +129,9 @@ pub fn ComboboxOptions<'cx, T: Clone + Hash + Eq + 'static, G: Html>(
         element.set_dyn_attr(cx, "tabindex", move || tabindex.to_string());
         element.set_dyn_bool(cx, "disabled", move || *disabled.get());
         element.set_dyn_bool(cx, "aria-multiselectable", move || context.multiple);
+        element.set_dyn_attr(cx, "aria-busy", move || {
+            (*context.loading.get() || loading_more.get()).to_string()
+        });
         element.set_attribute(
             "aria-orientation".into(),
             if context.horizontal {
@@ -115,6 +155,13 @@ pub fn ComboboxOptions<'cx, T: Clone + Hash + Eq + 'static, G: Html>(
                     node_ref: as_static(node_ref),
                 },
             );
+            provide_context(
+                cx,
+                PanelTransitionContext {
+                    state: transition_state,
+                    unmount: props.unmount,
+                },
+            );
             view = transition(cx, as_static(properties.open));
         });
         let element = node_ref.get_raw();
@@ -125,18 +172,93 @@ pub fn ComboboxOptions<'cx, T: Clone + Hash + Eq + 'static, G: Html>(
         let element = view.as_node().unwrap();
         apply_attributes(element);
 
-        View::new_dyn(cx, move || {
-            if *properties.open.get() {
-                view.clone()
-            } else {
-                View::empty()
-            }
+        if props.unmount {
+            View::new_dyn(cx, move || {
+                if *properties.open.get() {
+                    view.clone()
+                } else {
+                    View::empty()
+                }
+            })
+        } else {
+            element.set_dyn_hidden(cx, move || !*properties.open.get());
+            view
+        }
+    }
+}
+
+// Offered when the query matches none of the options and values can be created.
+fn create_option_view<'cx, G: Html>(
+    cx: Scope<'cx>,
+    context: &'cx ComboboxContext,
+    label: Option<Box<dyn Fn(&str) -> String + 'cx>>,
+) -> View<G> {
+    if context.create.is_none() {
+        return View::empty();
+    }
+    let label = create_ref(cx, label);
+
+    let id = create_id();
+    let show = create_memo(cx, move || {
+        !context.query.get().trim().is_empty() && *context.option_count.get() == 0
+    });
+    let active = create_selector(cx, {
+        let id = id.clone();
+        move || context.active_id.get().as_ref().as_ref() == Some(&id)
+    });
+
+    create_effect(cx, move || {
+        if !*show.get() && *active.get_untracked() {
+            context.active_id.set(None);
+        }
+    });
+
+    let on_click = move |_| {
+        if let Some(create) = context.create.as_ref() {
+            create((*context.query.get_untracked()).clone());
+        }
+    };
+    let on_mouse_enter = {
+        let id = id.clone();
+        move |_| context.active_id.set(Some(id.clone()))
+    };
+
+    let element: DynamicElement<G> = li.into();
+    let view = element.call(cx);
+    let element = view.as_node().unwrap();
+
+    element.set_children(
+        cx,
+        view! { cx,
+            (match label {
+                Some(label) => label(&context.query.get()),
+                None => format!("Create \"{}\"", context.query.get()),
+            })
+        },
+    );
+    element.set_attribute("data-sh".into(), "combobox-create-option".into());
+    element.set_attribute("data-sh-create".into(), "".into());
+    element.set_attribute("id".into(), id.into());
+    element.set_attribute("role".into(), "option".into());
+    element.set_attribute("tabindex".into(), "-1".into());
+    element.set_attribute("data-sh-owner".into(), context.owner_id.clone().into());
+    element.set_dyn_bool(cx, "data-sh-active", move || *active.get());
+
+    element.event(cx, ev::click, on_click);
+    element.event(cx, ev::mousedown, |e: MouseEvent| e.prevent_default());
+    element.event(cx, ev::mouseenter, on_mouse_enter);
+
+    view! { cx,
+        (if *show.get() {
+            view.clone()
+        } else {
+            View::empty()
         })
     }
 }
 
 #[derive(Props)]
-pub struct ComboboxOptionProps<'cx, T: Eq + Hash + 'static, G: Html> {
+pub struct ComboboxOptionProps<'cx, T: 'static, G: Html> {
     value: T,
     #[prop(default, setter(into))]
     disabled: ReactiveBool<'cx>,
@@ -149,60 +271,31 @@ pub struct ComboboxOptionProps<'cx, T: Eq + Hash + 'static, G: Html> {
 }
 
 #[component]
-pub fn ComboboxOption<'cx, T: Clone + Eq + Hash + 'static, G: Html>(
+pub fn ComboboxOption<'cx, T: Clone + 'static, G: Html>(
     cx: Scope<'cx>,
     props: ComboboxOptionProps<'cx, T, G>,
 ) -> View<G> {
     let context: &ComboboxContext = use_context(cx);
-    let focus: &FocusNavigator<G> = as_static(use_context(cx));
     let disclosure: &DisclosureProperties = use_context(cx);
     let properties: &SelectProperties<T> = as_static(use_context(cx));
 
     let value = as_static(create_ref(cx, props.value));
 
     let disabled = create_memo(cx, move || {
-        properties.disabled.get() || props.disabled.get()
+        properties.disabled.get() || props.disabled.get() || properties.is_limited(value)
     });
     let node = get_ref(cx, &props.attributes);
 
-    let on_key_down = move |e: KeyboardEvent| {
-        if !*disabled.get() {
-            match e.key().as_str() {
-                "ArrowLeft" if context.horizontal => {
-                    e.prevent_default();
-                    focus.set_prev_checked(node, false);
-                }
-                "ArrowUp" if !context.horizontal => {
-                    e.prevent_default();
-                    focus.set_prev_checked(node, false);
-                }
-                "ArrowRight" if context.horizontal => {
-                    e.prevent_default();
-                    focus.set_next_checked(node, false);
-                }
-                "ArrowDown" if !context.horizontal => {
-                    e.prevent_default();
-                    focus.set_next_checked(node, false);
-                }
-                " " | "Enter" => {
-                    properties.select(value.clone());
-                    if !context.multiple {
-                        e.prevent_default();
-                        disclosure.open.set(false);
-                    }
-                }
-                "Home" => {
-                    e.prevent_default();
-                    focus.set_first_checked();
-                }
-                "End" => {
-                    e.prevent_default();
-                    focus.set_last_checked();
-                }
-                _ => {}
-            }
-        }
-    };
+    let id = create_id();
+    context
+        .option_count
+        .set(*context.option_count.get_untracked() + 1);
+    on_cleanup(cx, move || {
+        context
+            .option_count
+            .set(context.option_count.get_untracked().saturating_sub(1));
+    });
+    count_group_option(cx);
 
     let on_click = move |_| {
         if !*disabled.get() {
@@ -213,46 +306,56 @@ pub fn ComboboxOption<'cx, T: Clone + Eq + Hash + 'static, G: Html>(
         }
     };
 
-    let on_focus = move |_| {
-        if !*disabled.get() {
-            properties.focus(value.clone())
+    // Focus stays in the input while options are clicked.
+    let on_mouse_down = |e: MouseEvent| e.prevent_default();
+
+    let on_mouse_enter = {
+        let id = id.clone();
+        move |_| {
+            if !*disabled.get() {
+                context.active_id.set(Some(id.clone()));
+            }
         }
     };
 
-    let on_blur = move |_| {
-        if !*disabled.get() {
-            properties.blur();
+    create_effect(cx, {
+        let id = id.clone();
+        move || {
+            if context.active_id.get().as_ref().as_ref() == Some(&id) {
+                properties.focus(value.clone());
+            }
         }
-    };
+    });
 
-    create_effect(cx, move || {
-        if let Some(element) = node
-            .try_get::<DomNode>()
-            .map(|node| node.to_web_sys())
-            .as_ref()
-            .and_then(|node| node.dyn_ref::<HtmlElement>())
-        {
-            if *disclosure.open.get() && properties.is_selected_untracked(value) && !*disabled.get()
-            {
-                let _ = element.focus();
+    on_cleanup(cx, {
+        let id = id.clone();
+        move || {
+            if context.active_id.get_untracked().as_ref().as_ref() == Some(&id) {
+                context.active_id.set(None);
             }
         }
     });
 
     let selected = create_ref(cx, move || properties.is_selected(value));
     let class = class(cx, &props.attributes, props.class);
-    let children = props.children.call(cx);
+    let text = OptionText::new(cx);
+    let children = scoped_children(cx, props.children, {
+        let text = text.clone();
+        move |cx| provide_context(cx, text)
+    });
 
     props.attributes.exclude_keys(&[
-        "on:keydown",
         "on:click",
-        "on:focus",
-        "on:blur",
+        "on:mousedown",
+        "on:mouseenter",
+        "id",
         "role",
         "tabindex",
         "ref",
         "disabled",
         "aria-selected",
+        "aria-labelledby",
+        "aria-describedby",
     ]);
 
     let view = props.element.call(cx);
@@ -265,18 +368,90 @@ pub fn ComboboxOption<'cx, T: Clone + Eq + Hash + 'static, G: Html>(
     element.apply_attributes(cx, &props.attributes);
     element.set_attribute("data-sh".into(), "combobox-option".into());
 
+    element.set_attribute("id".into(), id.into());
     element.set_attribute("role".into(), "option".into());
     element.set_attribute("tabindex".into(), "-1".into());
     element.set_attribute("data-sh-owner".into(), context.owner_id.clone().into());
     element.set_dyn_bool(cx, "disabled", move || *disabled.get());
+    element.set_dyn_attr(cx, "data-sh-disabled", move || disabled.get().to_string());
     element.set_dyn_bool(cx, "aria-selected", selected);
     element.set_dyn_bool(cx, "data-sh-selected", selected);
     element.set_dyn_bool(cx, "data-sh-active", move || properties.is_active(value));
+    text.apply(cx, element);
 
-    element.event(cx, ev::keydown, on_key_down);
     element.event(cx, ev::click, on_click);
-    element.event(cx, ev::focus, on_focus);
-    element.event(cx, ev::blur, on_blur);
+    element.event(cx, ev::mousedown, on_mouse_down);
+    element.event(cx, ev::mouseenter, on_mouse_enter);
+
+    view
+}
+
+#[derive(Props)]
+pub struct ComboboxOptionLabelProps<'cx, G: Html> {
+    #[prop(default, setter(into))]
+    class: ReactiveStr<'cx>,
+    #[prop(default = div.into(), setter(into))]
+    element: DynamicElement<'cx, G>,
+    children: Children<'cx, G>,
+    attributes: Attributes<'cx, G>,
+}
+
+#[component]
+pub fn ComboboxOptionLabel<'cx, G: Html>(
+    cx: Scope<'cx>,
+    props: ComboboxOptionLabelProps<'cx, G>,
+) -> View<G> {
+    let text: &OptionText = use_context(cx);
+    let id = text.register_label(cx);
+
+    let class = class(cx, &props.attributes, props.class);
+    let children = props.children.call(cx);
+    props.attributes.exclude_keys(&["id", "data-sh"]);
+
+    let view = props.element.call(cx);
+    let element = view.as_node().unwrap();
+
+    element.set_dyn_attr(cx, "class", move || class.to_string());
+    element.set_children(cx, children);
+    element.apply_attributes(cx, &props.attributes);
+    element.set_attribute("data-sh".into(), "combobox-option-label".into());
+
+    element.set_attribute("id".into(), id.into());
+
+    view
+}
+
+#[derive(Props)]
+pub struct ComboboxOptionDescriptionProps<'cx, G: Html> {
+    #[prop(default, setter(into))]
+    class: ReactiveStr<'cx>,
+    #[prop(default = div.into(), setter(into))]
+    element: DynamicElement<'cx, G>,
+    children: Children<'cx, G>,
+    attributes: Attributes<'cx, G>,
+}
+
+#[component]
+pub fn ComboboxOptionDescription<'cx, G: Html>(
+    cx: Scope<'cx>,
+    props: ComboboxOptionDescriptionProps<'cx, G>,
+) -> View<G> {
+    let text: &OptionText = use_context(cx);
+    let id = text.register_description(cx);
+
+    let class = class(cx, &props.attributes, props.class);
+    let children = props.children.call(cx);
+    props.attributes.exclude_keys(&["id", "data-sh"]);
+
+    let view = props.element.call(cx);
+    let element = view.as_node().unwrap();
+
+    element.set_dyn_attr(cx, "class", move || class.to_string());
+    element.set_children(cx, children);
+    element.apply_attributes(cx, &props.attributes);
+    element.set_attribute("data-sh".into(), "combobox-option-description".into());
+
+    element.set_attribute("id".into(), id.into());
 
     view
 }
@@ -0,0 +1,248 @@
+use super::ComboboxContext;
+use crate::{
+    components::select::SelectProperties,
+    hooks::create_id,
+    utils::{class, scoped_children, SetDynAttr},
+};
+use std::{cell::Cell, collections::HashSet, rc::Rc};
+use sycamore::{
+    builder::prelude::{button, div, span},
+    prelude::*,
+    web::html::ev,
+};
+use sycamore_utils::{DynamicElement, ReactiveStr};
+use web_sys::MouseEvent;
+
+const VISUALLY_HIDDEN: &str = "position: absolute; width: 1px; height: 1px; padding: 0; \
+    margin: -1px; overflow: hidden; clip: rect(0, 0, 0, 0); white-space: nowrap; border: 0;";
+
+#[allow(unused)]
+#[derive(Props)]
+pub struct ComboboxSelectionProps<'cx, T: 'static, F, G: Html>
+where
+    F: Fn(BoundedScope<'_, 'cx>, T) -> View<G> + 'cx,
+{
+    view: F,
+    #[prop(setter(into))]
+    label: Option<Box<dyn Fn(&T) -> String + 'cx>>,
+    #[prop(default, setter(into))]
+    class: ReactiveStr<'cx>,
+    #[prop(default = div.into(), setter(into))]
+    element: DynamicElement<'cx, G>,
+    attributes: Attributes<'cx, G>,
+}
+
+#[component]
+pub fn ComboboxSelection<'cx, T, F, G: Html>(
+    cx: Scope<'cx>,
+    props: ComboboxSelectionProps<'cx, T, F, G>,
+) -> View<G>
+where
+    T: Clone + 'static,
+    F: Fn(BoundedScope<'_, 'cx>, T) -> View<G> + 'cx,
+{
+    let properties: &SelectProperties<T> = use_context(cx);
+    let view_fn = create_ref(cx, props.view);
+    let label = create_ref(cx, props.label);
+
+    // Chips follow the selection order, the previous chips are kept to announce changes.
+    let order = create_signal(cx, Vec::<T>::new());
+    let announcement = create_signal(cx, String::new());
+    let initialized = create_ref(cx, Cell::new(false));
+
+    create_effect(cx, move || {
+        let selected = properties.selected();
+        let previous = order.get_untracked();
+        let keys = |values: &[T]| {
+            values
+                .iter()
+                .map(|value| properties.key(value))
+                .collect::<HashSet<_>>()
+        };
+        let (selected_keys, previous_keys) = (keys(&selected), keys(&previous));
+        let added = selected
+            .iter()
+            .filter(|value| !previous_keys.contains(&properties.key(value)))
+            .collect::<Vec<_>>();
+        let removed = previous
+            .iter()
+            .filter(|value| !selected_keys.contains(&properties.key(value)))
+            .collect::<Vec<_>>();
+
+        if initialized.replace(true) && (!added.is_empty() || !removed.is_empty()) {
+            let message = match label.as_ref() {
+                Some(label) => added
+                    .iter()
+                    .map(|value| format!("{} added", label(value)))
+                    .chain(
+                        removed
+                            .iter()
+                            .map(|value| format!("{} removed", label(value))),
+                    )
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                None => format!("{} selected", selected.len()),
+            };
+            announcement.set(message);
+        }
+
+        order.set(selected);
+    });
+
+    let class = class(cx, &props.attributes, props.class);
+    props.attributes.exclude_keys(&["data-sh", "role", "class"]);
+
+    let view = props.element.call(cx);
+    let element = view.as_node().unwrap();
+
+    element.set_dyn_attr(cx, "class", move || class.to_string());
+    element.set_children(
+        cx,
+        view! { cx,
+            Keyed(
+                iterable = order,
+                view = move |cx, value| view_fn(cx, value),
+                key = move |value: &T| properties.key(value),
+            )
+            span(
+                role = "status",
+                aria-live = "polite",
+                aria-atomic = "true",
+                data-sh = "combobox-announcer",
+                style = VISUALLY_HIDDEN,
+            ) {
+                (announcement.get())
+            }
+        },
+    );
+    element.apply_attributes(cx, &props.attributes);
+    element.set_attribute("data-sh".into(), "combobox-selection".into());
+    element.set_attribute("role".into(), "list".into());
+
+    view
+}
+
+#[derive(Clone)]
+struct ComboboxChipContext {
+    id: String,
+}
+
+#[derive(Props)]
+pub struct ComboboxChipProps<'cx, T: 'static, G: Html> {
+    value: T,
+    #[prop(default, setter(into))]
+    class: ReactiveStr<'cx>,
+    #[prop(default = span.into(), setter(into))]
+    element: DynamicElement<'cx, G>,
+    children: Children<'cx, G>,
+    attributes: Attributes<'cx, G>,
+}
+
+#[component]
+pub fn ComboboxChip<'cx, T: Clone + 'static, G: Html>(
+    cx: Scope<'cx>,
+    props: ComboboxChipProps<'cx, T, G>,
+) -> View<G> {
+    let context: &ComboboxContext = use_context(cx);
+    let properties: &SelectProperties<T> = use_context(cx);
+
+    let id = create_id();
+    let value = props.value;
+    let remove = {
+        let properties = properties.clone();
+        Rc::new(move || properties.deselect(&value))
+    };
+    context.chips.borrow_mut().insert(id.clone(), remove);
+    on_cleanup(cx, {
+        let id = id.clone();
+        move || {
+            context.chips.borrow_mut().remove(&id);
+            if context.active_chip.get_untracked().as_ref().as_ref() == Some(&id) {
+                context.active_chip.set(None);
+            }
+        }
+    });
+
+    let active = create_selector(cx, {
+        let id = id.clone();
+        move || context.active_chip.get().as_ref().as_ref() == Some(&id)
+    });
+
+    let class = class(cx, &props.attributes, props.class);
+    let children = scoped_children(cx, props.children, {
+        let id = id.clone();
+        move |cx| provide_context(cx, ComboboxChipContext { id })
+    });
+    props.attributes.exclude_keys(&[
+        "id",
+        "role",
+        "class",
+        "data-sh",
+        "data-sh-owner",
+        "data-sh-active",
+    ]);
+
+    let view = props.element.call(cx);
+    let element = view.as_node().unwrap();
+
+    element.set_dyn_attr(cx, "class", move || class.to_string());
+    element.set_children(cx, children);
+    element.apply_attributes(cx, &props.attributes);
+    element.set_attribute("data-sh".into(), "combobox-chip".into());
+
+    element.set_attribute("id".into(), id.into());
+    element.set_attribute("role".into(), "listitem".into());
+    element.set_attribute("data-sh-owner".into(), context.owner_id.clone().into());
+    element.set_dyn_bool(cx, "data-sh-active", move || *active.get());
+
+    view
+}
+
+#[derive(Props)]
+pub struct ComboboxChipRemoveProps<'cx, G: Html> {
+    #[prop(default, setter(into))]
+    class: ReactiveStr<'cx>,
+    #[prop(default = button.into(), setter(into))]
+    element: DynamicElement<'cx, G>,
+    children: Children<'cx, G>,
+    attributes: Attributes<'cx, G>,
+}
+
+#[component]
+pub fn ComboboxChipRemove<'cx, G: Html>(
+    cx: Scope<'cx>,
+    props: ComboboxChipRemoveProps<'cx, G>,
+) -> View<G> {
+    let context: &ComboboxContext = use_context(cx);
+    let chip: &ComboboxChipContext = use_context(cx);
+
+    let class = class(cx, &props.attributes, props.class);
+    let children = props.children.call(cx);
+    props.attributes.exclude_keys(&[
+        "on:click",
+        "on:mousedown",
+        "class",
+        "type",
+        "tabindex",
+        "data-sh",
+    ]);
+
+    let view = props.element.call(cx);
+    let element = view.as_node().unwrap();
+
+    element.set_dyn_attr(cx, "class", move || class.to_string());
+    element.set_children(cx, children);
+    element.apply_attributes(cx, &props.attributes);
+    element.set_attribute("data-sh".into(), "combobox-chip-remove".into());
+
+    element.set_attribute("type".into(), "button".into());
+    // Chips are reached from the input with the arrow keys instead.
+    element.set_attribute("tabindex".into(), "-1".into());
+
+    element.event(cx, ev::click, move |_: MouseEvent| {
+        context.remove_chip(&chip.id)
+    });
+    element.event(cx, ev::mousedown, |e: MouseEvent| e.prevent_default());
+
+    view
+}
@@ -0,0 +1,110 @@
+#[cfg(target_arch = "wasm32")]
+use std::{
+    cell::{Cell, RefCell},
+    mem,
+    rc::Rc,
+};
+use std::{fmt::Display, future::Future};
+
+#[cfg(target_arch = "wasm32")]
+use gloo_timers::callback::Timeout;
+use sycamore::prelude::*;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_futures::spawn_local;
+
+use crate::utils::as_static;
+
+pub struct AsyncOptions<'cx, T: 'static> {
+    pub options: &'cx ReadSignal<Vec<T>>,
+    pub loading: &'cx ReadSignal<bool>,
+    pub error: &'cx ReadSignal<Option<String>>,
+    pub empty: &'cx ReadSignal<bool>,
+}
+
+impl<T> Clone for AsyncOptions<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for AsyncOptions<'_, T> {}
+
+pub fn create_async_options<'cx, T, E, F, Fut>(
+    cx: Scope<'cx>,
+    query: &'cx ReadSignal<String>,
+    load_options: F,
+    debounce: u32,
+) -> AsyncOptions<'cx, T>
+where
+    T: 'static,
+    E: Display,
+    F: Fn(String) -> Fut + 'cx,
+    Fut: Future<Output = Result<Vec<T>, E>> + 'static,
+{
+    let options = as_static(create_signal(cx, Vec::new()));
+    let loading = as_static(create_signal(cx, false));
+    let error = as_static(create_signal(cx, None));
+    let empty = create_memo(cx, move || {
+        !*loading.get() && error.get().is_none() && options.get().is_empty()
+    });
+
+    // Requests can only be made from the browser.
+    #[cfg(target_arch = "wasm32")]
+    {
+        let load_options: &dyn Fn(String) -> Fut = create_ref(cx, load_options);
+        let load_options: &'static dyn Fn(String) -> Fut = unsafe { mem::transmute(load_options) };
+        let pending = as_static(create_ref(cx, RefCell::new(None::<Timeout>)));
+        // Responses are only applied if no newer query has been issued since the request started.
+        let generation = Rc::new(Cell::new(0_u32));
+
+        on_cleanup(cx, {
+            let generation = generation.clone();
+            move || {
+                generation.set(generation.get().wrapping_add(1));
+                if let Some(timeout) = pending.borrow_mut().take() {
+                    timeout.cancel();
+                }
+            }
+        });
+
+        create_effect(cx, move || {
+            let query = (*query.get()).clone();
+            let current = generation.get().wrapping_add(1);
+            generation.set(current);
+            loading.set(true);
+
+            if let Some(timeout) = pending.borrow_mut().take() {
+                timeout.cancel();
+            }
+
+            let generation = generation.clone();
+            let load = move || {
+                let future = load_options(query);
+                spawn_local(async move {
+                    let result = future.await;
+                    if generation.get() != current {
+                        return;
+                    }
+                    match result {
+                        Ok(loaded) => {
+                            error.set(None);
+                            options.set(loaded);
+                        }
+                        Err(e) => error.set(Some(e.to_string())),
+                    }
+                    loading.set(false);
+                });
+            };
+            *pending.borrow_mut() = Some(Timeout::new(debounce, load));
+        });
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    let _ = (query, load_options, debounce);
+
+    AsyncOptions {
+        options,
+        loading,
+        error,
+        empty,
+    }
+}
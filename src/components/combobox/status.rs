@@ -0,0 +1,65 @@
+use super::ComboboxContext;
+use crate::utils::{class, SetDynAttr};
+use sycamore::{builder::prelude::li, prelude::*};
+use sycamore_utils::{DynamicElement, ReactiveStr};
+
+#[derive(Props)]
+pub struct ComboboxStatusProps<'cx, G: Html> {
+    #[prop(default, setter(into))]
+    class: ReactiveStr<'cx>,
+    #[prop(default = li.into(), setter(into))]
+    element: DynamicElement<'cx, G>,
+    children: Children<'cx, G>,
+    attributes: Attributes<'cx, G>,
+}
+
+fn render_status<'cx, G: Html>(
+    cx: Scope<'cx>,
+    props: ComboboxStatusProps<'cx, G>,
+    show: &'cx ReadSignal<bool>,
+    name: &'static str,
+    role: &'static str,
+) -> View<G> {
+    let class = class(cx, &props.attributes, props.class);
+    let children = props.children.call(cx);
+    props.attributes.exclude_keys(&["data-sh", "role"]);
+
+    let view = props.element.call(cx);
+    let element = view.as_node().unwrap();
+
+    element.set_dyn_attr(cx, "class", move || class.to_string());
+    element.set_children(cx, children);
+    element.apply_attributes(cx, &props.attributes);
+    element.set_attribute("data-sh".into(), name.into());
+    element.set_attribute("role".into(), role.into());
+
+    view! { cx,
+        (if *show.get() {
+            view.clone()
+        } else {
+            View::empty()
+        })
+    }
+}
+
+#[component]
+pub fn ComboboxLoading<'cx, G: Html>(
+    cx: Scope<'cx>,
+    props: ComboboxStatusProps<'cx, G>,
+) -> View<G> {
+    let context: &ComboboxContext = use_context(cx);
+    render_status(cx, props, context.loading, "combobox-loading", "status")
+}
+
+#[component]
+pub fn ComboboxEmpty<'cx, G: Html>(cx: Scope<'cx>, props: ComboboxStatusProps<'cx, G>) -> View<G> {
+    let context: &ComboboxContext = use_context(cx);
+    render_status(cx, props, context.empty, "combobox-empty", "status")
+}
+
+#[component]
+pub fn ComboboxError<'cx, G: Html>(cx: Scope<'cx>, props: ComboboxStatusProps<'cx, G>) -> View<G> {
+    let context: &ComboboxContext = use_context(cx);
+    let show = create_memo(cx, move || context.error.get().is_some());
+    render_status(cx, props, show, "combobox-error", "alert")
+}
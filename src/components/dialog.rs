@@ -17,7 +17,10 @@ use crate::{
     },
 };
 
-use super::{DisclosureProperties, TransitionContext, TransitionProp};
+use super::{
+    transition_children, DisclosureProperties, PanelTransitionContext, TransitionContext,
+    TransitionProp,
+};
 
 #[derive(Props)]
 pub struct DialogProps<'cx, G: Html> {
@@ -182,6 +185,8 @@ pub struct DialogPanelProps<'cx, G: Html> {
     #[prop(default = div.into(), setter(into))]
     element: DynamicElement<'cx, G>,
     transition: Option<TransitionProp<'cx, G>>,
+    #[prop(default = true)]
+    unmount: bool,
     children: Children<'cx, G>,
     attributes: Attributes<'cx, G>,
 }
@@ -218,7 +223,8 @@ pub fn DialogPanel<'cx, G: Html>(cx: Scope<'cx>, props: DialogPanelProps<'cx, G>
         }
     };
 
-    let children = props.children.call(cx);
+    let (children, transition_state) =
+        transition_children(cx, props.children, props.transition.is_some());
     let class = class(cx, &props.attributes, props.class);
 
     let apply_attributes = |element: &G| {
@@ -244,6 +250,13 @@ pub fn DialogPanel<'cx, G: Html>(cx: Scope<'cx>, props: DialogPanelProps<'cx, G>
                     node_ref: as_static(node_ref),
                 },
             );
+            provide_context(
+                cx,
+                PanelTransitionContext {
+                    state: transition_state,
+                    unmount: props.unmount,
+                },
+            );
             view = transition(cx, properties.open);
         });
         let element = node_ref.get_raw();
@@ -254,12 +267,17 @@ pub fn DialogPanel<'cx, G: Html>(cx: Scope<'cx>, props: DialogPanelProps<'cx, G>
         let element = view.as_node().unwrap();
         apply_attributes(element);
 
-        view! { cx,
-            (if *properties.open.get() {
-                view.clone()
-            } else {
-                View::empty()
-            })
+        if props.unmount {
+            view! { cx,
+                (if *properties.open.get() {
+                    view.clone()
+                } else {
+                    View::empty()
+                })
+            }
+        } else {
+            element.set_dyn_hidden(cx, move || !*properties.open.get());
+            view
         }
     }
 }
@@ -13,7 +13,7 @@ use crate::{
     utils::{as_static, class, get_ref, scoped_children, SetDynAttr},
 };
 
-use super::{TransitionContext, TransitionProp};
+use super::{transition_children, PanelTransitionContext, TransitionContext, TransitionProp};
 
 pub struct DisclosureContext {
     owner_id: String,
@@ -178,6 +178,8 @@ pub struct DisclosurePanelProps<'cx, G: Html> {
     #[prop(default, setter(into))]
     class: ReactiveStr<'cx>,
     transition: Option<TransitionProp<'cx, G>>,
+    #[prop(default = true)]
+    unmount: bool,
     #[prop(default = div.into(), setter(into))]
     element: DynamicElement<'cx, G>,
     children: Children<'cx, G>,
@@ -191,7 +193,8 @@ pub fn DisclosurePanel<'cx, G: Html>(
 ) -> View<G> {
     let context: &DisclosureContext = use_context(cx);
     let properties: &DisclosureProperties = use_context(cx);
-    let children = props.children.call(cx);
+    let (children, transition_state) =
+        transition_children(cx, props.children, props.transition.is_some());
 
     props.attributes.exclude_keys(&["id", "data-sh-owner"]);
     let class = class(cx, &props.attributes, props.class);
@@ -216,6 +219,13 @@ pub fn DisclosurePanel<'cx, G: Html>(
                     node_ref: as_static(node_ref),
                 },
             );
+            provide_context(
+                cx,
+                PanelTransitionContext {
+                    state: transition_state,
+                    unmount: props.unmount,
+                },
+            );
             view = transition(cx, as_static(properties.open));
         });
         let element = node_ref.get_raw();
@@ -226,12 +236,17 @@ pub fn DisclosurePanel<'cx, G: Html>(
         let element = view.as_node().unwrap();
         apply_attributes(element);
 
-        view! { cx,
-            (if *properties.open.get() {
-                view.clone()
-            } else {
-                View::empty()
-            })
+        if props.unmount {
+            view! { cx,
+                (if *properties.open.get() {
+                    view.clone()
+                } else {
+                    View::empty()
+                })
+            }
+        } else {
+            element.set_dyn_hidden(cx, move || !*properties.open.get());
+            view
         }
     }
 }
@@ -1,12 +1,18 @@
+use std::cell::RefCell;
+
 use super::ListboxContext;
 use crate::{
     components::DisclosureProperties,
-    utils::{class, SetDynAttr},
+    utils::{as_static, class, SetDynAttr},
 };
+use gloo_timers::callback::Timeout;
 use sycamore::{builder::prelude::button, prelude::*, web::html::ev};
 use sycamore_utils::{DynamicElement, ReactiveBool, ReactiveStr};
 use web_sys::{KeyboardEvent, MouseEvent};
 
+// Typed characters are combined into one query until the user pauses.
+const TYPEAHEAD_RESET: u32 = 500;
+
 #[derive(Props)]
 pub struct ListboxButtonProps<'cx, G: Html> {
     #[prop(default, setter(into))]
@@ -24,6 +30,44 @@ pub fn ListboxButton<'cx, G: Html>(cx: Scope<'cx>, props: ListboxButtonProps<'cx
     let context: &ListboxContext = use_context(cx);
     let properties: &DisclosureProperties = use_context(cx);
 
+    let characters = as_static(create_ref(cx, RefCell::new(String::new())));
+    let reset = as_static(create_ref::<RefCell<Option<Timeout>>>(
+        cx,
+        RefCell::new(None),
+    ));
+
+    on_cleanup(cx, || {
+        if let Some(reset) = reset.borrow_mut().take() {
+            reset.cancel();
+        }
+    });
+
+    // Selects the next option matching the typed characters like a native select does.
+    let select_match = move |key: &str| {
+        characters.borrow_mut().push_str(key);
+        if let Some(timeout) = reset.borrow_mut().take() {
+            timeout.cancel();
+        }
+        *reset.borrow_mut() = Some(Timeout::new(TYPEAHEAD_RESET, move || {
+            characters.borrow_mut().clear();
+        }));
+
+        let Some(list) = context.options_element.borrow().clone() else {
+            return;
+        };
+        let typed = characters.borrow().clone();
+        let mut chars = typed.chars();
+        let first = chars.next();
+        // Repeating a single character cycles through the options starting with it.
+        let repeated = chars.all(|c| Some(c) == first);
+        let query = if repeated {
+            first.map(String::from).unwrap_or_default()
+        } else {
+            typed
+        };
+        (context.select_match)(&list, &query, repeated);
+    };
+
     let on_key_down = {
         let disabled = props.disabled.clone();
         let properties_disabled = properties.disabled.clone();
@@ -34,6 +78,16 @@ pub fn ListboxButton<'cx, G: Html>(cx: Scope<'cx>, props: ListboxButtonProps<'cx
                         e.prevent_default();
                         properties.open.set(!*properties.open.get_untracked());
                     }
+                    // Space still opens the list unless it continues a typed query.
+                    key if key.chars().count() == 1
+                        && !context.multiple
+                        && !*properties.open.get_untracked()
+                        && !(e.ctrl_key() || e.meta_key() || e.alt_key())
+                        && (key != " " || !characters.borrow().is_empty()) =>
+                    {
+                        e.prevent_default();
+                        select_match(key);
+                    }
                     _ => {}
                 }
             }
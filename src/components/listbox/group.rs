@@ -0,0 +1,33 @@
+use sycamore::prelude::*;
+
+use super::ListboxContext;
+use crate::components::select::{
+    select_group, select_group_label, select_separator, SelectGroupLabelProps, SelectGroupProps,
+    SelectSeparatorProps,
+};
+
+pub type ListboxGroupProps<'cx, G> = SelectGroupProps<'cx, G>;
+pub type ListboxGroupLabelProps<'cx, G> = SelectGroupLabelProps<'cx, G>;
+pub type ListboxSeparatorProps<'cx, G> = SelectSeparatorProps<'cx, G>;
+
+#[component]
+pub fn ListboxGroup<'cx, G: Html>(cx: Scope<'cx>, props: ListboxGroupProps<'cx, G>) -> View<G> {
+    select_group(cx, props, "listbox")
+}
+
+#[component]
+pub fn ListboxGroupLabel<'cx, G: Html>(
+    cx: Scope<'cx>,
+    props: ListboxGroupLabelProps<'cx, G>,
+) -> View<G> {
+    select_group_label(cx, props, "listbox")
+}
+
+#[component]
+pub fn ListboxSeparator<'cx, G: Html>(
+    cx: Scope<'cx>,
+    props: ListboxSeparatorProps<'cx, G>,
+) -> View<G> {
+    let context: &ListboxContext = use_context(cx);
+    select_separator(cx, props, "listbox", context.horizontal)
+}
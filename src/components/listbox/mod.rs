@@ -1,28 +1,40 @@
-use std::{collections::HashSet, hash::Hash, mem, rc::Rc};
+use std::{cell::RefCell, mem};
 
-use sycamore::{builder::prelude::div, prelude::*};
+use sycamore::{builder::prelude::div, prelude::*, rt::JsCast};
 use sycamore_utils::{DynamicElement, ReactiveBool, ReactiveStr};
+use web_sys::{Element, HtmlElement};
 
 use crate::{
     hooks::create_id,
-    utils::{as_static, class, get_ref, scoped_children, FocusStartPoint, SetDynAttr},
+    utils::{
+        as_static, class, focus_navigation::find_match, get_ref, scoped_children, FocusStartPoint,
+        SetDynAttr,
+    },
     FocusNavigator,
 };
 
 mod button;
+mod group;
 mod label;
 mod options;
 
 pub use button::*;
+pub use group::*;
 pub use label::*;
 pub use options::*;
 
-use super::{DisclosureProperties, SelectProperties, SelectValue};
+use super::{
+    provide_select_validity, use_headless_select, DisclosureProperties, HeadlessSelectOptions,
+    SelectBy, SelectProperties, SelectSignal, SelectValue, VirtualListSlot,
+};
 
 #[derive(Props)]
-pub struct ListBoxProps<'cx, T: Clone + Eq + Hash + 'static, G: Html> {
-    value: Option<&'cx Signal<Option<T>>>,
-    value_multiple: Option<&'cx Signal<HashSet<T>>>,
+pub struct ListBoxProps<'cx, T: Clone + 'static, G: Html> {
+    #[prop(setter(into))]
+    value: Option<SelectSignal<'cx, Option<T>, T>>,
+    /// Selected values, kept in the order they were picked in.
+    #[prop(setter(into))]
+    value_multiple: Option<SelectSignal<'cx, Vec<T>, T>>,
     open: Option<&'cx Signal<bool>>,
     #[prop(default)]
     default_open: bool,
@@ -35,6 +47,13 @@ pub struct ListBoxProps<'cx, T: Clone + Eq + Hash + 'static, G: Html> {
     #[prop(default)]
     toggleable: bool,
     #[prop(default, setter(into))]
+    by: SelectBy<T>,
+    #[prop(default)]
+    required: bool,
+    min_selected: Option<usize>,
+    max_selected: Option<usize>,
+    valid: Option<&'cx Signal<bool>>,
+    #[prop(default, setter(into))]
     class: ReactiveStr<'cx>,
     #[prop(default = div.into(), setter(into))]
     element: DynamicElement<'cx, G>,
@@ -50,10 +69,14 @@ pub struct ListboxContext {
     options_id: String,
     horizontal: bool,
     hovering: &'static Signal<bool>,
+    // Kept while the list is closed so the button can match option texts.
+    options_element: RefCell<Option<HtmlElement>>,
+    // Selects the option matching a query typed on the button, see `select_match`.
+    select_match: Box<dyn Fn(&HtmlElement, &str, bool)>,
 }
 
 #[component]
-pub fn Listbox<'cx, T: Clone + Eq + Hash + 'static, G: Html>(
+pub fn Listbox<'cx, T: Clone + 'static, G: Html>(
     cx: Scope<'cx>,
     props: ListBoxProps<'cx, T, G>,
 ) -> View<G> {
@@ -68,7 +91,7 @@ pub fn Listbox<'cx, T: Clone + Eq + Hash + 'static, G: Html>(
     let options_id = create_id();
 
     let focus_start = FocusStartPoint::new(cx);
-    let context = ListboxContext {
+    let mut context = ListboxContext {
         multiple: props.value_multiple.is_some(),
         owner_id: owner_id.clone(),
         label_id: label_id.clone(),
@@ -76,40 +99,69 @@ pub fn Listbox<'cx, T: Clone + Eq + Hash + 'static, G: Html>(
         options_id,
         horizontal: props.horizontal,
         hovering: unsafe { mem::transmute(hovering) },
+        options_element: RefCell::new(None),
+        select_match: Box::new(|_, _, _| {}),
     };
 
-    let active = create_signal::<Option<Rc<T>>>(cx, None);
     let disclosure_properties = DisclosureProperties {
         open: unsafe { mem::transmute(open) },
         disabled: unsafe { mem::transmute(props.disabled.clone()) },
     };
-    let properties = if let Some(value) = props.value {
-        SelectProperties::<T> {
-            value: SelectValue::Single(unsafe { mem::transmute(value) }),
-            active: unsafe { mem::transmute(active) },
-            disabled: unsafe { mem::transmute(props.disabled.clone()) },
-            toggleable: props.toggleable,
-        }
+    let (value, by) = if let Some(value) = props.value {
+        let (value, by) = value.into_parts();
+        (SelectValue::Single(as_static(value)), by)
     } else if let Some(value) = props.value_multiple {
-        SelectProperties::<T> {
-            value: SelectValue::Multiple(unsafe { mem::transmute(value) }),
-            active: unsafe { mem::transmute(active) },
-            disabled: unsafe { mem::transmute(props.disabled.clone()) },
-            toggleable: props.toggleable,
-        }
+        let (value, by) = value.into_parts();
+        (SelectValue::Multiple(as_static(value)), by)
     } else {
         return view! { cx, span { "Must provide either 'value' or 'value_multiple'." } };
     };
+    let by = props.by.or(by);
+    if !by.is_set() {
+        return view! { cx, span { "Must provide 'by' for values without a 'SelectKey'." } };
+    }
+    let properties = use_headless_select(
+        cx,
+        HeadlessSelectOptions {
+            value,
+            disabled: unsafe { mem::transmute(props.disabled.clone()) },
+            toggleable: props.toggleable,
+            by,
+            required: props.required,
+            min_selected: props.min_selected,
+            max_selected: props.max_selected,
+        },
+    );
+    let valid = properties.valid();
+    let virtual_list = VirtualListSlot::default();
+
+    context.select_match = Box::new({
+        let properties = properties.clone();
+        let virtual_list = virtual_list.clone();
+        let owner_id = owner_id.clone();
+        move |list, query, next| {
+            select_match(&properties, &virtual_list, list, &owner_id, query, next)
+        }
+    });
+
+    create_effect(cx, move || {
+        if let Some(signal) = props.valid {
+            signal.set(*valid.get());
+        }
+    });
 
     let internal_ref = get_ref(cx, &props.attributes);
     let children = scoped_children(cx, props.children, |cx| {
         provide_context(cx, context);
+        provide_select_validity(cx, &properties);
         provide_context(cx, properties);
         provide_context(cx, disclosure_properties);
         provide_context(
             cx,
-            FocusNavigator::new(owner_id.clone(), as_static(internal_ref)),
+            FocusNavigator::new(owner_id.clone(), as_static(internal_ref))
+                .with_virtual_list(virtual_list.clone()),
         );
+        provide_context(cx, virtual_list);
     });
 
     create_effect(cx, move || {
@@ -125,9 +177,13 @@ pub fn Listbox<'cx, T: Clone + Eq + Hash + 'static, G: Html>(
     });
     let class = class(cx, &props.attributes, props.class);
 
-    props
-        .attributes
-        .exclude_keys(&["data-sh", "id", "aria-labelledby", "disabled"]);
+    props.attributes.exclude_keys(&[
+        "data-sh",
+        "id",
+        "aria-labelledby",
+        "disabled",
+        "data-sh-invalid",
+    ]);
 
     let view = props.element.call(cx);
     let element = view.as_node().unwrap();
@@ -142,6 +198,48 @@ pub fn Listbox<'cx, T: Clone + Eq + Hash + 'static, G: Html>(
     element.set_attribute("id".into(), owner_id.into());
     element.set_attribute("aria-labelledby".into(), label_id.into());
     element.set_dyn_bool(cx, "disabled", move || props.disabled.get());
+    element.set_dyn_bool(cx, "data-sh-invalid", move || !*valid.get());
 
     view
 }
+
+// Selects the first option from the selected one on whose text starts with `query`, starting
+// after the selected one if `next`. Typing never clears the value, even if `toggleable`.
+fn select_match<T: Clone + 'static>(
+    properties: &SelectProperties<T>,
+    virtual_list: &VirtualListSlot,
+    list: &HtmlElement,
+    owner_id: &str,
+    query: &str,
+    next: bool,
+) {
+    if let Some(virtual_list) = virtual_list.get() {
+        let start = virtual_list
+            .selected()
+            .map_or(0, |selected| selected + usize::from(next));
+        if let Some(found) = virtual_list.find(query, start) {
+            if let Some(index) = found {
+                virtual_list.select(index);
+            }
+            return;
+        }
+    }
+
+    let Ok(nodes) =
+        list.query_selector_all(&format!("[role=\"option\"][data-sh-owner=\"{owner_id}\"]"))
+    else {
+        return;
+    };
+    let selected = (0..nodes.length()).find(|i| {
+        nodes
+            .get(*i)
+            .and_then(|node| node.dyn_into::<Element>().ok())
+            .map_or(false, |option| option.has_attribute("aria-selected"))
+    });
+    let start = selected.map_or(0, |selected| selected + u32::from(next));
+    if let Some(value) =
+        find_match(&nodes, query, start).and_then(|option| properties.options.get(&option.id()))
+    {
+        properties.select_without_toggle(value);
+    }
+}
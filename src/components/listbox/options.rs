@@ -1,22 +1,28 @@
-use std::{cell::RefCell, hash::Hash};
+use std::cell::RefCell;
 
 use super::ListboxContext;
 use crate::{
     components::{
-        select::SelectProperties, DisclosureProperties, TransitionContext, TransitionProp,
+        select::{count_group_option, SelectProperties},
+        transition_children, DisclosureProperties, PanelTransitionContext, TransitionContext,
+        TransitionProp,
+    },
+    hooks::create_id,
+    utils::{
+        as_static, class, focus_navigation::as_html_element, get_ref, load_more_sentinel,
+        scoped_children, OptionText, SetDynAttr,
     },
-    utils::{as_static, class, get_ref, SetDynAttr},
     FocusNavigator,
 };
 use gloo_timers::callback::Timeout;
 use sycamore::{
-    builder::prelude::{li, ul},
+    builder::prelude::{div, li, ul},
     prelude::*,
     rt::JsCast,
     web::html::ev,
 };
 use sycamore_utils::{DynamicElement, ReactiveBool, ReactiveStr};
-use web_sys::{FocusEvent, HtmlElement, KeyboardEvent};
+use web_sys::{FocusEvent, HtmlElement, KeyboardEvent, MouseEvent};
 
 #[derive(Props)]
 pub struct ListboxOptionsProps<'cx, G: Html> {
@@ -25,6 +31,14 @@ pub struct ListboxOptionsProps<'cx, G: Html> {
     #[prop(default, setter(into))]
     class: ReactiveStr<'cx>,
     transition: Option<TransitionProp<'cx, G>>,
+    #[prop(default = true)]
+    unmount: bool,
+    #[prop(setter(into))]
+    on_load_more: Option<Box<dyn Fn() + 'cx>>,
+    #[prop(default, setter(into))]
+    has_more: ReactiveBool<'cx>,
+    #[prop(default, setter(into))]
+    loading_more: ReactiveBool<'cx>,
     #[prop(default = ul.into(), setter(into))]
     element: DynamicElement<'cx, G>,
     children: Children<'cx, G>,
@@ -32,7 +46,7 @@ pub struct ListboxOptionsProps<'cx, G: Html> {
 }
 
 #[component]
-pub fn ListboxOptions<'cx, T: Clone + Hash + Eq + 'static, G: Html>(
+pub fn ListboxOptions<'cx, T: Clone + 'static, G: Html>(
     cx: Scope<'cx>,
     props: ListboxOptionsProps<'cx, G>,
 ) -> View<G> {
@@ -66,7 +80,16 @@ pub fn ListboxOptions<'cx, T: Clone + Hash + Eq + 'static, G: Html>(
     });
     let tabindex = create_memo(cx, move || if *disabled.get() { -1 } else { 0 });
     let class = class(cx, &props.attributes, props.class);
-    let children = props.children.call(cx);
+    let (children, transition_state) =
+        transition_children(cx, props.children, props.transition.is_some());
+    let loading_more = props.loading_more.clone();
+    let load_more = load_more_sentinel(
+        cx,
+        "listbox-load-more",
+        props.on_load_more,
+        props.has_more,
+        props.loading_more,
+    );
 
     props.attributes.exclude_keys(&[
         "on:focusout",
@@ -76,15 +99,20 @@ pub fn ListboxOptions<'cx, T: Clone + Hash + Eq + 'static, G: Html>(
         "aria-multiselectable",
         "aria-labelledby",
         "aria-orientation",
+        "aria-busy",
         "tabindex",
         "disabled",
     ]);
 
+    on_cleanup(cx, move || {
+        context.options_element.borrow_mut().take();
+    });
     let apply_attributes = |element: &G| {
         internal_ref.set(element.clone());
+        *context.options_element.borrow_mut() = as_html_element(internal_ref);
 
         element.set_dyn_attr(cx, "class", move || class.to_string());
-        element.set_children(cx, children);
+        element.set_children(cx, view! { cx, (children) (load_more) });
         element.apply_attributes(cx, &props.attributes);
         element.set_attribute("data-sh".into(), "listbox-options".into());
 
@@ -93,6 +121,7 @@ pub fn ListboxOptions<'cx, T: Clone + Hash + Eq + 'static, G: Html>(
         element.set_attribute("aria-labelledby".into(), context.button_id.clone().into());
         element.set_dyn_attr(cx, "tabindex", move || tabindex.to_string());
         element.set_dyn_bool(cx, "aria-multiselectable", move || context.multiple);
+        element.set_dyn_attr(cx, "aria-busy", move || loading_more.get().to_string());
         element.set_dyn_bool(cx, "disabled", move || *disabled.get());
         element.set_attribute(
             "aria-orientation".into(),
@@ -117,6 +146,13 @@ pub fn ListboxOptions<'cx, T: Clone + Hash + Eq + 'static, G: Html>(
                     node_ref: as_static(node_ref),
                 },
             );
+            provide_context(
+                cx,
+                PanelTransitionContext {
+                    state: transition_state,
+                    unmount: props.unmount,
+                },
+            );
             view = transition(cx, as_static(properties.open));
         });
         let element = node_ref.get_raw();
@@ -127,18 +163,23 @@ pub fn ListboxOptions<'cx, T: Clone + Hash + Eq + 'static, G: Html>(
         let element = view.as_node().unwrap();
         apply_attributes(element);
 
-        view! { cx,
-            (if *properties.open.get() {
-                view.clone()
-            } else {
-                View::empty()
-            })
+        if props.unmount {
+            view! { cx,
+                (if *properties.open.get() {
+                    view.clone()
+                } else {
+                    View::empty()
+                })
+            }
+        } else {
+            element.set_dyn_hidden(cx, move || !*properties.open.get());
+            view
         }
     }
 }
 
 #[derive(Props)]
-pub struct ListboxOptionProps<'cx, T: Eq + Hash + 'static, G: Html> {
+pub struct ListboxOptionProps<'cx, T: 'static, G: Html> {
     value: T,
     #[prop(default, setter(into))]
     disabled: ReactiveBool<'cx>,
@@ -151,7 +192,7 @@ pub struct ListboxOptionProps<'cx, T: Eq + Hash + 'static, G: Html> {
 }
 
 #[component]
-pub fn ListboxOption<'cx, T: Clone + Eq + Hash + 'static, G: Html>(
+pub fn ListboxOption<'cx, T: Clone + 'static, G: Html>(
     cx: Scope<'cx>,
     props: ListboxOptionProps<'cx, T, G>,
 ) -> View<G> {
@@ -161,6 +202,13 @@ pub fn ListboxOption<'cx, T: Clone + Eq + Hash + 'static, G: Html>(
     let properties: &SelectProperties<T> = as_static(use_context(cx));
 
     let value = as_static(create_ref(cx, props.value));
+    count_group_option(cx);
+    let id = create_id();
+    properties.options.register(id.clone(), value.clone());
+    on_cleanup(cx, {
+        let id = id.clone();
+        move || properties.options.unregister(&id)
+    });
 
     let characters = as_static(create_ref(cx, RefCell::new(String::new())));
     let delay = as_static(create_ref::<RefCell<Option<Timeout>>>(
@@ -175,28 +223,58 @@ pub fn ListboxOption<'cx, T: Clone + Eq + Hash + 'static, G: Html>(
     });
 
     let disabled = create_memo(cx, move || {
-        properties.disabled.get() || props.disabled.get()
+        properties.disabled.get() || props.disabled.get() || properties.is_limited(value)
     });
     let node = get_ref(cx, &props.attributes);
 
+    let enabled_options = move || {
+        node.try_get::<DomNode>()
+            .and_then(|node| {
+                node.to_web_sys()
+                    .unchecked_into::<HtmlElement>()
+                    .closest("[role=\"listbox\"]")
+                    .ok()
+                    .flatten()
+            })
+            .map(|list| {
+                properties
+                    .options
+                    .enabled(list.unchecked_ref(), &context.owner_id)
+            })
+            .unwrap_or_default()
+    };
+    // Moves focus and extends the selection from the anchor to the newly active option.
+    let extend = move |e: &KeyboardEvent, forward: bool| {
+        e.prevent_default();
+        if context.multiple && e.shift_key() && properties.anchor.get_untracked().is_none() {
+            properties.anchor.set(Some(value.clone()));
+        }
+        if forward {
+            focus.set_next_checked(node, false);
+        } else {
+            focus.set_prev_checked(node, false);
+        }
+        if context.multiple && e.shift_key() {
+            if let Some(active) = properties.active.get_untracked().as_ref().clone() {
+                properties.select_range(&enabled_options(), active);
+            }
+        }
+    };
+
     let on_key_down = move |e: KeyboardEvent| {
         if !*disabled.get() {
             match e.key().as_str() {
-                "ArrowLeft" if context.horizontal => {
-                    e.prevent_default();
-                    focus.set_prev_checked(node, false);
-                }
-                "ArrowUp" if !context.horizontal => {
-                    e.prevent_default();
-                    focus.set_prev_checked(node, false);
-                }
-                "ArrowRight" if context.horizontal => {
+                "ArrowLeft" if context.horizontal => extend(&e, false),
+                "ArrowUp" if !context.horizontal => extend(&e, false),
+                "ArrowRight" if context.horizontal => extend(&e, true),
+                "ArrowDown" if !context.horizontal => extend(&e, true),
+                " " if context.multiple => {
                     e.prevent_default();
-                    focus.set_next_checked(node, false);
+                    properties.toggle(value.clone());
                 }
-                "ArrowDown" if !context.horizontal => {
+                "a" | "A" if context.multiple && (e.ctrl_key() || e.meta_key()) => {
                     e.prevent_default();
-                    focus.set_next_checked(node, false);
+                    properties.select_all(&enabled_options());
                 }
                 " " | "Enter" => {
                     properties.select(value.clone());
@@ -228,9 +306,15 @@ pub fn ListboxOption<'cx, T: Clone + Eq + Hash + 'static, G: Html>(
         }
     };
 
-    let on_click = move |_| {
+    let on_click = move |e: MouseEvent| {
         if !*disabled.get() {
-            properties.select(value.clone());
+            if context.multiple && e.shift_key() {
+                properties.select_range(&enabled_options(), value.clone());
+            } else if context.multiple && (e.ctrl_key() || e.meta_key()) {
+                properties.toggle(value.clone());
+            } else {
+                properties.select(value.clone());
+            }
             if !context.multiple {
                 disclosure.open.set(false);
             }
@@ -256,7 +340,19 @@ pub fn ListboxOption<'cx, T: Clone + Eq + Hash + 'static, G: Html>(
             .as_ref()
             .and_then(|node| node.dyn_ref::<HtmlElement>())
         {
-            if *disclosure.open.get() && properties.is_selected_untracked(value) && !*disabled.get()
+            // Virtualized options are recreated while scrolling, they must not steal focus then.
+            let focus_in_list = element
+                .closest("[role=\"listbox\"]")
+                .ok()
+                .flatten()
+                .zip(web_sys::window().and_then(|window| window.document()?.active_element()))
+                .map_or(false, |(list, active)| {
+                    list.contains(Some(active.unchecked_ref()))
+                });
+            if *disclosure.open.get()
+                && properties.is_selected_untracked(value)
+                && !*disabled.get()
+                && !focus_in_list
             {
                 let _ = element.focus();
             }
@@ -265,18 +361,26 @@ pub fn ListboxOption<'cx, T: Clone + Eq + Hash + 'static, G: Html>(
 
     let selected = create_ref(cx, move || properties.is_selected(value));
     let class = class(cx, &props.attributes, props.class);
-    let children = props.children.call(cx);
+    let text = OptionText::new(cx);
+    let children = scoped_children(cx, props.children, {
+        let text = text.clone();
+        move |cx| provide_context(cx, text)
+    });
 
     props.attributes.exclude_keys(&[
         "on:keydown",
         "on:click",
         "on:focus",
         "on:blur",
+        "id",
         "role",
         "tabindex",
         "ref",
         "disabled",
+        "data-sh-disabled",
         "aria-selected",
+        "aria-labelledby",
+        "aria-describedby",
     ]);
 
     let view = props.element.call(cx);
@@ -289,13 +393,16 @@ pub fn ListboxOption<'cx, T: Clone + Eq + Hash + 'static, G: Html>(
     element.apply_attributes(cx, &props.attributes);
     element.set_attribute("data-sh".into(), "listbox-option".into());
 
+    element.set_attribute("id".into(), id.into());
     element.set_attribute("role".into(), "option".into());
     element.set_attribute("tabindex".into(), "-1".into());
     element.set_attribute("data-sh-owner".into(), context.owner_id.clone().into());
     element.set_dyn_bool(cx, "disabled", move || *disabled.get());
+    element.set_dyn_attr(cx, "data-sh-disabled", move || disabled.get().to_string());
     element.set_dyn_bool(cx, "aria-selected", selected);
     element.set_dyn_bool(cx, "data-sh-selected", selected);
     element.set_dyn_bool(cx, "data-sh-active", move || properties.is_active(value));
+    text.apply(cx, element);
 
     element.event(cx, ev::keydown, on_key_down);
     element.event(cx, ev::click, on_click);
@@ -304,3 +411,73 @@ pub fn ListboxOption<'cx, T: Clone + Eq + Hash + 'static, G: Html>(
 
     view
 }
+
+#[derive(Props)]
+pub struct ListboxOptionLabelProps<'cx, G: Html> {
+    #[prop(default, setter(into))]
+    class: ReactiveStr<'cx>,
+    #[prop(default = div.into(), setter(into))]
+    element: DynamicElement<'cx, G>,
+    children: Children<'cx, G>,
+    attributes: Attributes<'cx, G>,
+}
+
+#[component]
+pub fn ListboxOptionLabel<'cx, G: Html>(
+    cx: Scope<'cx>,
+    props: ListboxOptionLabelProps<'cx, G>,
+) -> View<G> {
+    let text: &OptionText = use_context(cx);
+    let id = text.register_label(cx);
+
+    let class = class(cx, &props.attributes, props.class);
+    let children = props.children.call(cx);
+    props.attributes.exclude_keys(&["id", "data-sh"]);
+
+    let view = props.element.call(cx);
+    let element = view.as_node().unwrap();
+
+    element.set_dyn_attr(cx, "class", move || class.to_string());
+    element.set_children(cx, children);
+    element.apply_attributes(cx, &props.attributes);
+    element.set_attribute("data-sh".into(), "listbox-option-label".into());
+
+    element.set_attribute("id".into(), id.into());
+
+    view
+}
+
+#[derive(Props)]
+pub struct ListboxOptionDescriptionProps<'cx, G: Html> {
+    #[prop(default, setter(into))]
+    class: ReactiveStr<'cx>,
+    #[prop(default = div.into(), setter(into))]
+    element: DynamicElement<'cx, G>,
+    children: Children<'cx, G>,
+    attributes: Attributes<'cx, G>,
+}
+
+#[component]
+pub fn ListboxOptionDescription<'cx, G: Html>(
+    cx: Scope<'cx>,
+    props: ListboxOptionDescriptionProps<'cx, G>,
+) -> View<G> {
+    let text: &OptionText = use_context(cx);
+    let id = text.register_description(cx);
+
+    let class = class(cx, &props.attributes, props.class);
+    let children = props.children.call(cx);
+    props.attributes.exclude_keys(&["id", "data-sh"]);
+
+    let view = props.element.call(cx);
+    let element = view.as_node().unwrap();
+
+    element.set_dyn_attr(cx, "class", move || class.to_string());
+    element.set_children(cx, children);
+    element.apply_attributes(cx, &props.attributes);
+    element.set_attribute("data-sh".into(), "listbox-option-description".into());
+
+    element.set_attribute("id".into(), id.into());
+
+    view
+}
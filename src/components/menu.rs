@@ -8,7 +8,8 @@ use web_sys::{HtmlElement, KeyboardEvent};
 use crate::{
     hooks::create_id,
     utils::{
-        as_static, class, focus_navigator::FocusNavigator, get_ref, scoped_children, SetDynAttr,
+        as_static, class, focus_navigator::FocusNavigator, get_ref, scoped_children, OptionText,
+        SetDynAttr,
     },
 };
 
@@ -129,10 +130,20 @@ pub fn MenuItem<'cx, G: Html>(cx: Scope<'cx>, props: MenuItemProps<'cx, G>) -> V
         }
     };
 
-    let children = props.children.call(cx);
-    props
-        .attributes
-        .exclude_keys(&["data-sh-owner", "role", "tabindex", "ref", "on:keydown"]);
+    let text = OptionText::new(cx);
+    let children = scoped_children(cx, props.children, {
+        let text = text.clone();
+        move |cx| provide_context(cx, text)
+    });
+    props.attributes.exclude_keys(&[
+        "data-sh-owner",
+        "role",
+        "tabindex",
+        "ref",
+        "on:keydown",
+        "aria-labelledby",
+        "aria-describedby",
+    ]);
     let class = class(cx, &props.attributes, props.class);
 
     let view = props.element.call(cx);
@@ -148,8 +159,76 @@ pub fn MenuItem<'cx, G: Html>(cx: Scope<'cx>, props: MenuItemProps<'cx, G>) -> V
     element.set_attribute("role".into(), "menuitem".into());
     element.set_attribute("data-sh".into(), "menu-item".into());
     element.set_attribute("tabindex".into(), "-1".into());
+    text.apply(cx, element);
 
     element.event(cx, ev::keydown, on_key_down);
 
     view
 }
+
+#[derive(Props)]
+pub struct MenuItemLabelProps<'cx, G: Html> {
+    #[prop(default, setter(into))]
+    class: ReactiveStr<'cx>,
+    #[prop(default = div.into(), setter(into))]
+    element: DynamicElement<'cx, G>,
+    children: Children<'cx, G>,
+    attributes: Attributes<'cx, G>,
+}
+
+#[component]
+pub fn MenuItemLabel<'cx, G: Html>(cx: Scope<'cx>, props: MenuItemLabelProps<'cx, G>) -> View<G> {
+    let text: &OptionText = use_context(cx);
+    let id = text.register_label(cx);
+
+    let class = class(cx, &props.attributes, props.class);
+    let children = props.children.call(cx);
+    props.attributes.exclude_keys(&["id", "data-sh"]);
+
+    let view = props.element.call(cx);
+    let element = view.as_node().unwrap();
+
+    element.set_dyn_attr(cx, "class", move || class.to_string());
+    element.set_children(cx, children);
+    element.apply_attributes(cx, &props.attributes);
+    element.set_attribute("data-sh".into(), "menu-item-label".into());
+
+    element.set_attribute("id".into(), id.into());
+
+    view
+}
+
+#[derive(Props)]
+pub struct MenuItemDescriptionProps<'cx, G: Html> {
+    #[prop(default, setter(into))]
+    class: ReactiveStr<'cx>,
+    #[prop(default = div.into(), setter(into))]
+    element: DynamicElement<'cx, G>,
+    children: Children<'cx, G>,
+    attributes: Attributes<'cx, G>,
+}
+
+#[component]
+pub fn MenuItemDescription<'cx, G: Html>(
+    cx: Scope<'cx>,
+    props: MenuItemDescriptionProps<'cx, G>,
+) -> View<G> {
+    let text: &OptionText = use_context(cx);
+    let id = text.register_description(cx);
+
+    let class = class(cx, &props.attributes, props.class);
+    let children = props.children.call(cx);
+    props.attributes.exclude_keys(&["id", "data-sh"]);
+
+    let view = props.element.call(cx);
+    let element = view.as_node().unwrap();
+
+    element.set_dyn_attr(cx, "class", move || class.to_string());
+    element.set_children(cx, children);
+    element.apply_attributes(cx, &props.attributes);
+    element.set_attribute("data-sh".into(), "menu-item-description".into());
+
+    element.set_attribute("id".into(), id.into());
+
+    view
+}
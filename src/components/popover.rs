@@ -18,7 +18,10 @@ use crate::{
     },
 };
 
-use super::{DisclosureProperties, TransitionContext, TransitionProp};
+use super::{
+    transition_children, DisclosureProperties, PanelTransitionContext, TransitionContext,
+    TransitionProp,
+};
 
 #[derive(Props)]
 pub struct PopoverProps<'cx, G: Html> {
@@ -234,6 +237,8 @@ pub struct PopoverPanelProps<'cx, G: Html> {
     #[prop(default, setter(into))]
     disabled: ReactiveBool<'cx>,
     transition: Option<TransitionProp<'cx, G>>,
+    #[prop(default = true)]
+    unmount: bool,
     #[prop(default = div.into(), setter(into))]
     element: DynamicElement<'cx, G>,
     #[prop(default, setter(into))]
@@ -259,7 +264,8 @@ pub fn PopoverPanel<'cx, G: Html>(cx: Scope<'cx>, props: PopoverPanelProps<'cx,
         }
     });
 
-    let children = props.children.call(cx);
+    let (children, transition_state) =
+        transition_children(cx, props.children, props.transition.is_some());
     let class = class(cx, &props.attributes, props.class);
 
     let apply_props = |element: &G| {
@@ -315,6 +321,13 @@ pub fn PopoverPanel<'cx, G: Html>(cx: Scope<'cx>, props: PopoverPanelProps<'cx,
                     node_ref: as_static(node_ref),
                 },
             );
+            provide_context(
+                cx,
+                PanelTransitionContext {
+                    state: transition_state,
+                    unmount: props.unmount,
+                },
+            );
             view = transition(cx, as_static(properties.open));
         });
         let element = node_ref.get_raw();
@@ -325,12 +338,17 @@ pub fn PopoverPanel<'cx, G: Html>(cx: Scope<'cx>, props: PopoverPanelProps<'cx,
         let element = view.as_node().unwrap();
         apply_props(element);
 
-        view! { cx,
-            (if *show.get() {
-                view.clone()
-            } else {
-                View::empty()
-            })
+        if props.unmount {
+            view! { cx,
+                (if *show.get() {
+                    view.clone()
+                } else {
+                    View::empty()
+                })
+            }
+        } else {
+            element.set_dyn_hidden(cx, move || !*show.get());
+            view
         }
     }
 }
@@ -1,4 +1,4 @@
-use std::{hash::Hash, mem};
+use std::mem;
 use sycamore::{
     builder::prelude::{div, label},
     component::Attributes,
@@ -13,11 +13,17 @@ use crate::{
     utils::{class, focus_navigator::FocusNavigator, get_ref, scoped_children, SetDynAttr},
 };
 
-use super::{use_headless_select_single, HeadlessSelectSingleOptions, SelectProperties};
+use super::{
+    use_headless_select_single, HeadlessSelectSingleOptions, SelectBy, SelectProperties,
+    SelectSignal,
+};
 
 #[derive(Props)]
-pub struct RadioGroupProps<'cx, T, G: Html> {
-    value: &'cx Signal<Option<T>>,
+pub struct RadioGroupProps<'cx, T: 'static, G: Html> {
+    #[prop(setter(into))]
+    value: SelectSignal<'cx, Option<T>, T>,
+    #[prop(default, setter(into))]
+    by: SelectBy<T>,
     #[prop(default, setter(into))]
     disabled: ReactiveBool<'cx>,
     #[prop(default, setter(into))]
@@ -34,7 +40,7 @@ pub struct RadioGroupContext {
 }
 
 #[component]
-pub fn RadioGroup<'cx, T: Clone + Eq + Hash + 'static, G: Html>(
+pub fn RadioGroup<'cx, T: Clone + 'static, G: Html>(
     cx: Scope<'cx>,
     props: RadioGroupProps<'cx, T, G>,
 ) -> View<G> {
@@ -46,12 +52,18 @@ pub fn RadioGroup<'cx, T: Clone + Eq + Hash + 'static, G: Html>(
         description_id: description_id.clone(),
         label_id: label_id.clone(),
     };
+    let (value, by) = props.value.into_parts();
+    let by = props.by.or(by);
+    if !by.is_set() {
+        return view! { cx, span { "Must provide 'by' for values without a 'SelectKey'." } };
+    }
     let select_context = use_headless_select_single::<T>(
         cx,
         HeadlessSelectSingleOptions {
-            value: unsafe { mem::transmute(props.value) },
+            value: unsafe { mem::transmute(value) },
             disabled: unsafe { mem::transmute(props.disabled.clone()) },
             toggleable: false,
+            by,
         },
     );
 
@@ -155,7 +167,7 @@ pub fn RadioGroupDescription<'cx, G: Html>(
 }
 
 #[derive(Props)]
-pub struct RadioGroupOptionProps<'cx, T: PartialEq, G: Html> {
+pub struct RadioGroupOptionProps<'cx, T: 'static, G: Html> {
     value: T,
     #[prop(default, setter(into))]
     disabled: ReactiveBool<'cx>,
@@ -168,7 +180,7 @@ pub struct RadioGroupOptionProps<'cx, T: PartialEq, G: Html> {
 }
 
 #[component]
-pub fn RadioGroupOption<'cx, T: Clone + Eq + Hash + 'static, G: Html>(
+pub fn RadioGroupOption<'cx, T: Clone + 'static, G: Html>(
     cx: Scope<'cx>,
     props: RadioGroupOptionProps<'cx, T, G>,
 ) -> View<G> {
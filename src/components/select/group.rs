@@ -0,0 +1,153 @@
+use sycamore::{
+    builder::prelude::{div, li},
+    prelude::*,
+};
+use sycamore_utils::{DynamicElement, ReactiveStr};
+
+use crate::{
+    hooks::create_id,
+    utils::{as_static, class, scoped_children, SetDynAttr},
+};
+
+#[derive(Props)]
+pub struct SelectGroupProps<'cx, G: Html> {
+    #[prop(default, setter(into))]
+    class: ReactiveStr<'cx>,
+    #[prop(default = li.into(), setter(into))]
+    element: DynamicElement<'cx, G>,
+    children: Children<'cx, G>,
+    attributes: Attributes<'cx, G>,
+}
+
+#[derive(Props)]
+pub struct SelectGroupLabelProps<'cx, G: Html> {
+    #[prop(default, setter(into))]
+    class: ReactiveStr<'cx>,
+    #[prop(default = div.into(), setter(into))]
+    element: DynamicElement<'cx, G>,
+    children: Children<'cx, G>,
+    attributes: Attributes<'cx, G>,
+}
+
+#[derive(Props)]
+pub struct SelectSeparatorProps<'cx, G: Html> {
+    #[prop(default, setter(into))]
+    class: ReactiveStr<'cx>,
+    #[prop(default = li.into(), setter(into))]
+    element: DynamicElement<'cx, G>,
+    attributes: Attributes<'cx, G>,
+}
+
+// Listbox and combobox groups only differ in the `data-sh` prefix of their parts.
+pub(crate) struct SelectGroupContext {
+    label_id: String,
+    option_count: &'static Signal<usize>,
+}
+
+// Groups are hidden while none of their options are rendered, e.g. after filtering.
+pub(crate) fn count_group_option(cx: Scope) {
+    if let Some(group) = try_use_context::<SelectGroupContext>(cx) {
+        group
+            .option_count
+            .set(*group.option_count.get_untracked() + 1);
+        on_cleanup(cx, move || {
+            group
+                .option_count
+                .set(group.option_count.get_untracked().saturating_sub(1));
+        });
+    }
+}
+
+pub(crate) fn select_group<'cx, G: Html>(
+    cx: Scope<'cx>,
+    props: SelectGroupProps<'cx, G>,
+    prefix: &str,
+) -> View<G> {
+    let label_id = create_id();
+    let option_count = create_signal(cx, 0usize);
+
+    let children = scoped_children(cx, props.children, {
+        let label_id = label_id.clone();
+        move |cx| {
+            provide_context(
+                cx,
+                SelectGroupContext {
+                    label_id,
+                    option_count: as_static(option_count),
+                },
+            );
+        }
+    });
+
+    let class = class(cx, &props.attributes, props.class);
+    props
+        .attributes
+        .exclude_keys(&["data-sh", "role", "aria-labelledby"]);
+
+    let view = props.element.call(cx);
+    let element = view.as_node().unwrap();
+
+    element.set_dyn_attr(cx, "class", move || class.to_string());
+    element.set_children(cx, children);
+    element.apply_attributes(cx, &props.attributes);
+    element.set_attribute("data-sh".into(), format!("{prefix}-group").into());
+
+    element.set_attribute("role".into(), "group".into());
+    element.set_attribute("aria-labelledby".into(), label_id.into());
+    element.set_dyn_hidden(cx, move || *option_count.get() == 0);
+
+    view
+}
+
+pub(crate) fn select_group_label<'cx, G: Html>(
+    cx: Scope<'cx>,
+    props: SelectGroupLabelProps<'cx, G>,
+    prefix: &str,
+) -> View<G> {
+    let context: &SelectGroupContext = use_context(cx);
+
+    let class = class(cx, &props.attributes, props.class);
+    let children = props.children.call(cx);
+    props.attributes.exclude_keys(&["id", "data-sh", "role"]);
+
+    let view = props.element.call(cx);
+    let element = view.as_node().unwrap();
+
+    element.set_dyn_attr(cx, "class", move || class.to_string());
+    element.set_children(cx, children);
+    element.apply_attributes(cx, &props.attributes);
+    element.set_attribute("data-sh".into(), format!("{prefix}-group-label").into());
+
+    element.set_attribute("id".into(), context.label_id.clone().into());
+    element.set_attribute("role".into(), "presentation".into());
+
+    view
+}
+
+pub(crate) fn select_separator<'cx, G: Html>(
+    cx: Scope<'cx>,
+    props: SelectSeparatorProps<'cx, G>,
+    prefix: &str,
+    horizontal: bool,
+) -> View<G> {
+    let class = class(cx, &props.attributes, props.class);
+    props
+        .attributes
+        .exclude_keys(&["data-sh", "role", "aria-orientation"]);
+
+    let view = props.element.call(cx);
+    let element = view.as_node().unwrap();
+
+    element.set_dyn_attr(cx, "class", move || class.to_string());
+    element.apply_attributes(cx, &props.attributes);
+    element.set_attribute("data-sh".into(), format!("{prefix}-separator").into());
+
+    element.set_attribute("role".into(), "separator".into());
+    // Separators run across the list, perpendicular to its orientation.
+    element.set_attribute(
+        "aria-orientation".into(),
+        if horizontal { "vertical" } else { "horizontal" }.into(),
+    );
+
+    view
+}
@@ -1,77 +1,474 @@
-use std::{borrow::Borrow, collections::HashSet, hash::Hash, mem, rc::Rc};
+use std::{
+    any::Any,
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    hash::{Hash, Hasher},
+    rc::Rc,
+};
 
-use sycamore::prelude::*;
+use sycamore::{prelude::*, rt::JsCast};
 use sycamore_utils::ReactiveBool;
+use web_sys::HtmlElement;
+
+use crate::utils::as_static;
+
+mod group;
+mod virtual_options;
+
+pub use group::*;
+pub use virtual_options::*;
 
 pub struct HeadlessSelectSingleOptions<T: 'static> {
     pub value: &'static Signal<Option<T>>,
     pub disabled: ReactiveBool<'static>,
     pub toggleable: bool,
+    /// Must be set, e.g. through [`SelectBy::select_key`].
+    pub by: SelectBy<T>,
 }
 
-pub struct SelectProperties<T: Clone + Eq + Hash + 'static> {
+pub struct HeadlessSelectOptions<T: 'static> {
     pub value: SelectValue<T>,
-    pub active: &'static Signal<Option<T>>,
     pub disabled: ReactiveBool<'static>,
     pub toggleable: bool,
+    /// Must be set, e.g. through [`SelectBy::select_key`].
+    pub by: SelectBy<T>,
+    pub required: bool,
+    pub min_selected: Option<usize>,
+    pub max_selected: Option<usize>,
 }
 
-pub enum SelectValue<T: Eq + Hash + 'static> {
-    Single(&'static Signal<Option<T>>),
-    Multiple(&'static Signal<HashSet<T>>),
+/// Identifies a value in a selection, so values themselves don't need to be comparable. Every
+/// `Clone + Eq + Hash` type is its own key, other types return e.g. their id.
+pub trait SelectKey {
+    type Key: Eq + Hash + 'static;
+
+    fn select_key(&self) -> Self::Key;
+}
+
+impl<T: Clone + Eq + Hash + 'static> SelectKey for T {
+    type Key = T;
+
+    fn select_key(&self) -> T {
+        self.clone()
+    }
+}
+
+trait DynKey {
+    fn as_any(&self) -> &dyn Any;
+    fn eq_key(&self, other: &dyn Any) -> bool;
+    fn hash_key(&self, state: &mut dyn Hasher);
+}
+
+impl<K: Eq + Hash + 'static> DynKey for K {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn eq_key(&self, other: &dyn Any) -> bool {
+        other.downcast_ref::<K>() == Some(self)
+    }
+
+    fn hash_key(&self, mut state: &mut dyn Hasher) {
+        self.hash(&mut state);
+    }
+}
+
+// Keys of different `by` functions have different types, selections store them type erased.
+#[derive(Clone)]
+pub(crate) struct AnyKey(Rc<dyn DynKey>);
+
+impl AnyKey {
+    fn new<K: Eq + Hash + 'static>(key: K) -> Self {
+        Self(Rc::new(key))
+    }
+}
+
+impl PartialEq for AnyKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq_key(other.0.as_any())
+    }
 }
 
-impl<T: Clone + Eq + Hash + 'static> SelectProperties<T> {
-    pub fn is_selected<Q>(&self, value: &Q) -> bool
+impl Eq for AnyKey {}
+
+impl Hash for AnyKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash_key(state);
+    }
+}
+
+/// The key a selection identifies values by. Components take it from [`SelectSignal`] unless
+/// `by` overrides it.
+pub struct SelectBy<T: 'static>(Option<Rc<dyn Fn(&T) -> AnyKey>>);
+
+impl<T> Clone for SelectBy<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> Default for SelectBy<T> {
+    fn default() -> Self {
+        Self(None)
+    }
+}
+
+impl<T: 'static> SelectBy<T> {
+    pub fn key<K: Eq + Hash + 'static, F: Fn(&T) -> K + 'static>(key: F) -> Self {
+        Self(Some(Rc::new(move |value| AnyKey::new(key(value)))))
+    }
+
+    /// Identifies values by their [`SelectKey`].
+    pub fn select_key() -> Self
     where
-        T: Borrow<Q>,
-        Q: Eq + Hash,
+        T: SelectKey,
     {
-        match &self.value {
-            SelectValue::Single(selected) => {
-                if let Some(selected) = selected.get().as_ref() {
-                    selected.borrow() == value
-                } else {
-                    false
-                }
+        Self(Some(Rc::new(|value: &T| AnyKey::new(value.select_key()))))
+    }
+
+    pub fn is_set(&self) -> bool {
+        self.0.is_some()
+    }
+
+    pub(crate) fn or(self, fallback: Self) -> Self {
+        if self.is_set() {
+            self
+        } else {
+            fallback
+        }
+    }
+
+    pub(crate) fn key_of(&self, value: &T) -> AnyKey {
+        let key = self
+            .0
+            .as_ref()
+            .expect("values without a `SelectKey` need `by` to be selected");
+        key(value)
+    }
+}
+
+impl<T: 'static, K: Eq + Hash + 'static, F: Fn(&T) -> K + 'static> From<F> for SelectBy<T> {
+    fn from(key: F) -> Self {
+        Self::key(key)
+    }
+}
+
+/// A selection signal and the default key of its values. Signals of [`SelectKey`] values convert
+/// into it, other values are wrapped with [`SelectSignal::new`] and need `by`.
+pub struct SelectSignal<'cx, S: 'static, T: 'static> {
+    signal: &'cx Signal<S>,
+    by: SelectBy<T>,
+}
+
+impl<'cx, S, T> SelectSignal<'cx, S, T> {
+    pub fn new(signal: &'cx Signal<S>) -> Self {
+        Self {
+            signal,
+            by: SelectBy::default(),
+        }
+    }
+
+    pub(crate) fn into_parts(self) -> (&'cx Signal<S>, SelectBy<T>) {
+        (self.signal, self.by)
+    }
+}
+
+impl<'cx, T: SelectKey + 'static> From<&'cx Signal<Option<T>>> for SelectSignal<'cx, Option<T>, T> {
+    fn from(signal: &'cx Signal<Option<T>>) -> Self {
+        Self {
+            signal,
+            by: SelectBy::select_key(),
+        }
+    }
+}
+
+impl<'cx, T: SelectKey + 'static> From<&'cx Signal<Vec<T>>> for SelectSignal<'cx, Vec<T>, T> {
+    fn from(signal: &'cx Signal<Vec<T>>) -> Self {
+        Self {
+            signal,
+            by: SelectBy::select_key(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SelectProperties<T: Clone + 'static> {
+    pub value: SelectValue<T>,
+    pub active: &'static Signal<Option<T>>,
+    /// The fixed end of range selections in multiple mode.
+    pub anchor: &'static Signal<Option<T>>,
+    pub disabled: ReactiveBool<'static>,
+    pub toggleable: bool,
+    pub by: SelectBy<T>,
+    /// A single selection must have a value to be valid.
+    pub required: bool,
+    pub min_selected: Option<usize>,
+    /// Once reached, the unselected options are disabled.
+    pub max_selected: Option<usize>,
+    pub(crate) options: SelectOptions<T>,
+    // Keys of the selected values, so lookups don't compare against every value.
+    selected_keys: &'static ReadSignal<HashSet<AnyKey>>,
+    full: &'static ReadSignal<bool>,
+    valid: &'static ReadSignal<bool>,
+}
+
+// Values of the options in display order, needed for range selection.
+pub(crate) struct SelectOptions<T: 'static> {
+    rendered: Rc<RefCell<HashMap<String, T>>>,
+    source: Rc<RefCell<Option<Box<dyn Fn() -> Vec<T>>>>>,
+}
+
+impl<T> Clone for SelectOptions<T> {
+    fn clone(&self) -> Self {
+        Self {
+            rendered: self.rendered.clone(),
+            source: self.source.clone(),
+        }
+    }
+}
+
+impl<T> Default for SelectOptions<T> {
+    fn default() -> Self {
+        Self {
+            rendered: Rc::default(),
+            source: Rc::default(),
+        }
+    }
+}
+
+impl<T: Clone> SelectOptions<T> {
+    pub fn register(&self, id: String, value: T) {
+        self.rendered.borrow_mut().insert(id, value);
+    }
+
+    pub fn unregister(&self, id: &str) {
+        self.rendered.borrow_mut().remove(id);
+    }
+
+    pub fn get(&self, id: &str) -> Option<T> {
+        self.rendered.borrow().get(id).cloned()
+    }
+
+    /// Virtualized lists provide all of their enabled options instead of the rendered ones.
+    pub fn set_source(&self, source: Option<Box<dyn Fn() -> Vec<T>>>) {
+        *self.source.borrow_mut() = source;
+    }
+
+    pub fn enabled(&self, list: &HtmlElement, owner_id: &str) -> Vec<T> {
+        if let Some(source) = self.source.borrow().as_ref() {
+            return source();
+        }
+        let Ok(nodes) = list.query_selector_all(&format!(
+            "[role=\"option\"][data-sh-owner=\"{owner_id}\"]:not([disabled])"
+        )) else {
+            return Vec::new();
+        };
+        let rendered = self.rendered.borrow();
+        (0..nodes.length())
+            .filter_map(|i| {
+                let node: HtmlElement = nodes.get(i)?.dyn_into().ok()?;
+                rendered.get(&node.id()).cloned()
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone)]
+pub enum SelectValue<T: 'static> {
+    Single(&'static Signal<Option<T>>),
+    Multiple(&'static Signal<Vec<T>>),
+}
+
+impl<T: Clone + 'static> SelectProperties<T> {
+    pub(crate) fn key(&self, value: &T) -> AnyKey {
+        self.by.key_of(value)
+    }
+
+    fn insert(&self, selected: &Signal<Vec<T>>, value: T) {
+        if !self.is_selected_untracked(&value) && !self.is_full_untracked() {
+            selected.modify().push(value);
+        }
+    }
+
+    fn remove(&self, selected: &Signal<Vec<T>>, value: &T) {
+        if self.is_selected_untracked(value) {
+            let key = self.key(value);
+            selected
+                .modify()
+                .retain(|selected| self.key(selected) != key);
+        }
+    }
+
+    // Replaces the selection with `values`, already selected ones keep their place in front.
+    fn replace(&self, selected: &Signal<Vec<T>>, values: Vec<T>) {
+        let keys = values
+            .iter()
+            .map(|value| self.key(value))
+            .collect::<HashSet<_>>();
+        let mut next_keys = HashSet::with_capacity(keys.len());
+        let mut next = selected
+            .get_untracked()
+            .iter()
+            .filter(|value| {
+                let key = self.key(value);
+                keys.contains(&key) && next_keys.insert(key)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        for value in values {
+            if self.max_selected.map_or(false, |max| next.len() >= max) {
+                break;
+            }
+            if next_keys.insert(self.key(&value)) {
+                next.push(value);
             }
-            SelectValue::Multiple(selected) => selected.get().contains(value),
         }
+        selected.set(next);
     }
 
-    pub fn is_selected_untracked<Q>(&self, value: &Q) -> bool
-    where
-        T: Borrow<Q>,
-        Q: Eq + Hash,
-    {
+    /// The selected values, multiple selections are in the order they were picked in.
+    pub fn selected(&self) -> Vec<T> {
         match &self.value {
-            SelectValue::Single(selected) => {
-                if let Some(selected) = selected.get_untracked().as_ref() {
-                    selected.borrow() == value
-                } else {
-                    false
-                }
-            }
-            SelectValue::Multiple(selected) => selected.get_untracked().contains(value),
+            SelectValue::Single(selected) => selected.get().iter().cloned().collect(),
+            SelectValue::Multiple(selected) => (*selected.get()).clone(),
         }
     }
 
+    pub fn selected_count(&self) -> usize {
+        match &self.value {
+            SelectValue::Single(selected) => selected.get().iter().count(),
+            SelectValue::Multiple(selected) => selected.get().len(),
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        *self.full.get()
+    }
+
+    fn is_full_untracked(&self) -> bool {
+        *self.full.get_untracked()
+    }
+
+    /// Whether `value` can't be picked because the maximum number of values is selected.
+    pub fn is_limited(&self, value: &T) -> bool {
+        self.is_full() && !self.is_selected(value)
+    }
+
+    /// Whether the selection satisfies `required`, `min_selected` and `max_selected`.
+    pub fn is_valid(&self) -> bool {
+        *self.valid.get()
+    }
+
+    /// [`Self::is_valid`] as a signal, also available to children through [`use_select_validity`].
+    pub fn valid(&self) -> &'static ReadSignal<bool> {
+        self.valid
+    }
+
+    pub fn is_selected(&self, value: &T) -> bool {
+        self.selected_keys.get().contains(&self.key(value))
+    }
+
+    pub fn is_selected_untracked(&self, value: &T) -> bool {
+        self.selected_keys
+            .get_untracked()
+            .contains(&self.key(value))
+    }
+
     pub fn select(&self, value: T) {
+        self.anchor.set(Some(value.clone()));
         match &self.value {
             SelectValue::Single(selected) => {
-                if self.toggleable && selected.get_untracked().as_ref().as_ref() == Some(&value) {
+                if self.toggleable && self.is_selected_untracked(&value) {
                     selected.set(None);
                 } else {
                     selected.set(Some(value));
                 }
             }
             SelectValue::Multiple(selected) => {
-                if self.toggleable && selected.get_untracked().contains(&value) {
-                    selected.modify().remove(&value);
+                if self.toggleable && self.is_selected_untracked(&value) {
+                    self.remove(selected, &value);
+                } else {
+                    self.insert(selected, value);
+                }
+            }
+        }
+    }
+
+    /// Selects `value` regardless of `toggleable`, an already selected value stays selected.
+    pub fn select_without_toggle(&self, value: T) {
+        if self.is_selected_untracked(&value) {
+            self.anchor.set(Some(value));
+        } else {
+            self.select(value);
+        }
+    }
+
+    /// Adds or removes a single value regardless of `toggleable`, keeping the rest selected.
+    pub fn toggle(&self, value: T) {
+        match &self.value {
+            SelectValue::Single(_) => self.select(value),
+            SelectValue::Multiple(selected) => {
+                self.anchor.set(Some(value.clone()));
+                if self.is_selected_untracked(&value) {
+                    self.remove(selected, &value);
+                } else {
+                    self.insert(selected, value);
+                }
+            }
+        }
+    }
+
+    /// Selects everything between the anchor and `value` in `options`, the anchor stays in place.
+    pub fn select_range(&self, options: &[T], value: T) {
+        let SelectValue::Multiple(selected) = &self.value else {
+            return self.select(value);
+        };
+        let anchor = (*self.anchor.get_untracked())
+            .clone()
+            .unwrap_or_else(|| value.clone());
+        let (anchor_key, value_key) = (self.key(&anchor), self.key(&value));
+        let start = options
+            .iter()
+            .position(|option| self.key(option) == anchor_key);
+        let end = options
+            .iter()
+            .position(|option| self.key(option) == value_key);
+        match (start, end) {
+            (Some(start), Some(end)) => {
+                // New values are added walking away from the anchor.
+                let range = if start <= end {
+                    options[start..=end].to_vec()
                 } else {
-                    selected.modify().insert(value);
+                    options[end..=start].iter().rev().cloned().collect()
+                };
+                self.replace(selected, range);
+                self.anchor.set(Some(anchor));
+            }
+            _ => self.select(value),
+        }
+    }
+
+    pub fn select_all(&self, options: &[T]) {
+        if let SelectValue::Multiple(selected) = &self.value {
+            self.replace(selected, options.to_vec());
+        }
+    }
+
+    pub fn deselect(&self, value: &T) {
+        match &self.value {
+            SelectValue::Single(selected) => {
+                if self.is_selected_untracked(value) {
+                    selected.set(None);
                 }
             }
+            SelectValue::Multiple(selected) => self.remove(selected, value),
+        }
+    }
+
+    pub fn clear(&self) {
+        match &self.value {
+            SelectValue::Single(selected) => selected.set(None),
+            SelectValue::Multiple(selected) => selected.modify().clear(),
         }
     }
 
@@ -86,16 +483,12 @@ impl<T: Clone + Eq + Hash + 'static> SelectProperties<T> {
         self.active.get().is_some()
     }
 
-    pub fn is_active<Q>(&self, value: &Q) -> bool
-    where
-        T: Borrow<Q>,
-        Q: Eq + Hash,
-    {
-        if let Some(active) = self.active.get().as_ref() {
-            active.borrow() == value
-        } else {
-            false
-        }
+    pub fn is_active(&self, value: &T) -> bool {
+        self.active
+            .get()
+            .as_ref()
+            .as_ref()
+            .map_or(false, |active| self.key(active) == self.key(value))
     }
 
     pub fn focus(&self, value: T) {
@@ -107,20 +500,120 @@ impl<T: Clone + Eq + Hash + 'static> SelectProperties<T> {
     }
 }
 
-pub fn use_headless_select_single<T: Clone + Hash + Eq>(
+pub fn use_headless_select<T: Clone + 'static>(
     cx: Scope<'_>,
-    options: HeadlessSelectSingleOptions<T>,
+    options: HeadlessSelectOptions<T>,
 ) -> SelectProperties<T> {
-    let HeadlessSelectSingleOptions {
+    let HeadlessSelectOptions {
         value,
         disabled,
         toggleable,
+        by,
+        required,
+        min_selected,
+        max_selected,
     } = options;
-    let active = create_signal::<Option<Rc<T>>>(cx, None);
+    let active = create_signal::<Option<T>>(cx, None);
+    let anchor = create_signal::<Option<T>>(cx, None);
+    let selected_keys = create_memo(cx, {
+        let value = value.clone();
+        let by = by.clone();
+        move || -> HashSet<AnyKey> {
+            match &value {
+                SelectValue::Single(selected) => {
+                    selected.get().iter().map(|item| by.key_of(item)).collect()
+                }
+                SelectValue::Multiple(selected) => {
+                    selected.get().iter().map(|item| by.key_of(item)).collect()
+                }
+            }
+        }
+    });
+    // Shared by every option, so each one only tracks its own selection while the list is full.
+    let full = create_memo(cx, {
+        let value = value.clone();
+        move || match &value {
+            SelectValue::Single(_) => false,
+            SelectValue::Multiple(selected) => {
+                max_selected.map_or(false, |max| selected.get().len() >= max)
+            }
+        }
+    });
+    let valid = create_memo(cx, {
+        let value = value.clone();
+        move || match &value {
+            SelectValue::Single(selected) => !required || selected.get().is_some(),
+            SelectValue::Multiple(selected) => {
+                let count = selected.get().len();
+                min_selected.map_or(true, |min| count >= min)
+                    && max_selected.map_or(true, |max| count <= max)
+            }
+        }
+    });
     SelectProperties {
-        active: unsafe { mem::transmute(active) },
-        value: SelectValue::Single(value),
+        active: as_static(active),
+        anchor: as_static(anchor),
+        value,
         disabled,
         toggleable,
+        by,
+        required,
+        min_selected,
+        max_selected,
+        options: SelectOptions::default(),
+        selected_keys: as_static(selected_keys),
+        full: as_static(full),
+        valid: as_static(valid),
+    }
+}
+
+pub fn use_headless_select_single<T: Clone + 'static>(
+    cx: Scope<'_>,
+    options: HeadlessSelectSingleOptions<T>,
+) -> SelectProperties<T> {
+    let HeadlessSelectSingleOptions {
+        value,
+        disabled,
+        toggleable,
+        by,
+    } = options;
+    use_headless_select(
+        cx,
+        HeadlessSelectOptions {
+            value: SelectValue::Single(value),
+            disabled,
+            toggleable,
+            by,
+            required: false,
+            min_selected: None,
+            max_selected: None,
+        },
+    )
+}
+
+#[derive(Clone, Copy)]
+struct SelectValidityContext {
+    valid: &'static ReadSignal<bool>,
+}
+
+// Not generic over the value type, so children can read it without naming `T`.
+pub(crate) fn provide_select_validity<T: Clone + 'static>(
+    cx: Scope<'_>,
+    properties: &SelectProperties<T>,
+) {
+    provide_context(
+        cx,
+        SelectValidityContext {
+            valid: properties.valid,
+        },
+    );
+}
+
+/// Whether the enclosing listbox or combobox satisfies its selection constraints.
+pub fn use_select_validity(cx: Scope<'_>) -> &ReadSignal<bool> {
+    match try_use_context::<SelectValidityContext>(cx) {
+        Some(context) => context.valid,
+        // Without constraints, every selection is valid.
+        None => create_signal(cx, true),
     }
 }
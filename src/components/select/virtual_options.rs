@@ -0,0 +1,406 @@
+use std::{cell::RefCell, collections::HashMap, mem, rc::Rc};
+
+#[cfg(target_arch = "wasm32")]
+use gloo_timers::callback::Timeout;
+use sycamore::{prelude::*, rt::JsCast};
+use wasm_bindgen::prelude::Closure;
+use web_sys::{Element, EventTarget, HtmlElement};
+
+use super::SelectProperties;
+use crate::utils::{as_static, focus_navigation::as_html_element};
+
+// Used until the scroll container could be measured.
+const FALLBACK_VIEWPORT: f64 = 400.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ItemHeight {
+    Fixed(f64),
+    /// Items are measured once rendered, the estimate is used for the ones that weren't yet.
+    Measured(f64),
+}
+
+// Type erased view of the full data set, so navigation doesn't depend on what is rendered.
+pub(crate) struct VirtualList<'cx> {
+    count: Box<dyn Fn() -> usize + 'cx>,
+    start: Box<dyn Fn() -> usize + 'cx>,
+    disabled: Box<dyn Fn(usize) -> bool + 'cx>,
+    text: Option<Box<dyn Fn(usize) -> String + 'cx>>,
+    selected: Box<dyn Fn() -> Option<usize> + 'cx>,
+    select: Box<dyn Fn(usize) + 'cx>,
+    scroll_to: Box<dyn Fn(usize) + 'cx>,
+}
+
+impl VirtualList<'_> {
+    fn rendered(&self, list: &HtmlElement, owner_id: &str) -> Vec<HtmlElement> {
+        let Ok(nodes) =
+            list.query_selector_all(&format!("[role=\"option\"][data-sh-owner=\"{owner_id}\"]"))
+        else {
+            return Vec::new();
+        };
+        (0..nodes.length())
+            .filter_map(|i| nodes.get(i)?.dyn_into::<HtmlElement>().ok())
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        (self.count)() == 0
+    }
+
+    pub fn index_of(
+        &self,
+        list: &HtmlElement,
+        owner_id: &str,
+        option: &HtmlElement,
+    ) -> Option<usize> {
+        self.rendered(list, owner_id)
+            .iter()
+            .position(|node| node == option)
+            .map(|position| (self.start)() + position)
+    }
+
+    pub fn step(&self, from: Option<usize>, forward: bool, wrap: bool) -> Option<usize> {
+        let count = (self.count)();
+        let mut current = from;
+        for _ in 0..count {
+            let next = match (current, forward) {
+                (None, true) => 0,
+                (None, false) => count - 1,
+                (Some(i), true) if i + 1 < count => i + 1,
+                (Some(_), true) if wrap => 0,
+                (Some(i), false) if i > 0 => i - 1,
+                (Some(_), false) if wrap => count - 1,
+                _ => return None,
+            };
+            if !(self.disabled)(next) {
+                return Some(next);
+            }
+            current = Some(next);
+        }
+        None
+    }
+
+    pub fn selected(&self) -> Option<usize> {
+        (self.selected)().filter(|index| !(self.disabled)(*index))
+    }
+
+    /// Searches from `start` on, wrapping around. `None` if no option texts were provided,
+    /// matching then falls back to the rendered options.
+    pub fn find(&self, query: &str, start: usize) -> Option<Option<usize>> {
+        let text = self.text.as_ref()?;
+        let query = query.to_lowercase();
+        let count = (self.count)();
+        Some((0..count).map(|i| (start + i) % count).find(|index| {
+            !(self.disabled)(*index) && text(*index).to_lowercase().starts_with(&query)
+        }))
+    }
+
+    /// Selects the item at `index` without toggling it.
+    pub fn select(&self, index: usize) {
+        (self.select)(index);
+    }
+
+    // Rendering is synchronous, so the option exists right after scrolling to it.
+    pub fn reveal(&self, list: &HtmlElement, owner_id: &str, index: usize) -> Option<HtmlElement> {
+        (self.scroll_to)(index);
+        let position = index.checked_sub((self.start)())?;
+        self.rendered(list, owner_id).into_iter().nth(position)
+    }
+}
+
+#[derive(Clone, Default)]
+pub(crate) struct VirtualListSlot(Rc<RefCell<Option<Rc<VirtualList<'static>>>>>);
+
+impl VirtualListSlot {
+    pub fn get(&self) -> Option<Rc<VirtualList<'static>>> {
+        self.0.borrow().clone()
+    }
+}
+
+#[allow(unused)]
+#[derive(Props)]
+pub struct VirtualOptionsProps<'cx, T: 'static, F, G: Html>
+where
+    F: Fn(BoundedScope<'_, 'cx>, T) -> View<G> + 'cx,
+{
+    items: &'cx ReadSignal<Vec<T>>,
+    view: F,
+    item_height: ItemHeight,
+    #[prop(default = 5)]
+    overscan: usize,
+    #[prop(setter(into))]
+    text: Option<Box<dyn Fn(&T) -> String + 'cx>>,
+    #[prop(setter(into))]
+    disabled: Option<Box<dyn Fn(&T) -> bool + 'cx>>,
+}
+
+#[component]
+pub fn VirtualOptions<'cx, T, F, G: Html>(
+    cx: Scope<'cx>,
+    props: VirtualOptionsProps<'cx, T, F, G>,
+) -> View<G>
+where
+    T: Clone + 'static,
+    F: Fn(BoundedScope<'_, 'cx>, T) -> View<G> + 'cx,
+{
+    let properties: &SelectProperties<T> = use_context(cx);
+    let slot: &VirtualListSlot = use_context(cx);
+
+    let items = props.items;
+    let view_fn = create_ref(cx, props.view);
+    let item_height = props.item_height;
+    let overscan = props.overscan;
+
+    let scroll_top = create_signal(cx, 0.0);
+    let viewport = create_signal(cx, 0.0);
+    let measured = create_signal(cx, HashMap::<usize, f64>::new());
+
+    let offsets = create_memo(cx, move || {
+        let measured = measured.get();
+        let mut top = 0.0;
+        let mut offsets = vec![top];
+        for index in 0..items.get().len() {
+            top += match item_height {
+                ItemHeight::Fixed(height) => height,
+                ItemHeight::Measured(estimate) => measured.get(&index).copied().unwrap_or(estimate),
+            };
+            offsets.push(top);
+        }
+        offsets
+    });
+    let viewport_height = move || match *viewport.get() {
+        height if height > 0.0 => height,
+        _ => FALLBACK_VIEWPORT,
+    };
+    let range = create_memo(cx, move || {
+        let offsets = offsets.get();
+        let count = offsets.len() - 1;
+        let top = *scroll_top.get();
+        let bottom = top + viewport_height();
+        let start = offsets
+            .partition_point(|offset| *offset <= top)
+            .saturating_sub(1);
+        let end = offsets
+            .partition_point(|offset| *offset < bottom)
+            .min(count);
+        (
+            start.saturating_sub(overscan).min(count),
+            (end + overscan).min(count),
+        )
+    });
+    // `items` can change before `range` caught up, so the range is clamped to the current items.
+    let visible = create_memo(cx, move || {
+        let (start, end) = *range.get();
+        let items = items.get();
+        let len = items.len();
+        items[start.min(len)..end.min(len)].to_vec()
+    });
+
+    let top_spacer = create_node_ref(cx);
+    let bottom_spacer = create_node_ref(cx);
+
+    // The options list is expected to be the scroll container.
+    let elements = move || {
+        let spacer = as_html_element(top_spacer)?;
+        let container = spacer.parent_element()?.dyn_into::<HtmlElement>().ok()?;
+        Some((spacer, container))
+    };
+    let origin = |spacer: &HtmlElement, container: &HtmlElement| {
+        spacer.get_bounding_client_rect().top() - container.get_bounding_client_rect().top()
+            + container.scroll_top() as f64
+    };
+    let sync = create_ref(cx, move || {
+        if let Some((spacer, container)) = elements() {
+            viewport.set(container.client_height() as f64);
+            scroll_top.set((container.scroll_top() as f64 - origin(&spacer, &container)).max(0.0));
+        }
+    });
+    let scroll_to = move |index: usize| {
+        let offsets = offsets.get_untracked();
+        let (Some(top), Some(bottom)) = (offsets.get(index), offsets.get(index + 1)) else {
+            return;
+        };
+        let current = *scroll_top.get_untracked();
+        let height = viewport_height();
+        let next = if *top < current {
+            *top
+        } else if *bottom > current + height {
+            bottom - height
+        } else {
+            return;
+        };
+        if let Some((spacer, container)) = elements() {
+            container.set_scroll_top((next + origin(&spacer, &container)).round() as i32);
+        }
+        scroll_top.set(next);
+    };
+
+    let text = create_ref(cx, props.text);
+    let disabled = create_ref(cx, props.disabled);
+    let list = VirtualList {
+        count: Box::new(move || items.get_untracked().len()),
+        start: Box::new(move || range.get_untracked().0),
+        disabled: Box::new(move |index| {
+            items.get_untracked().get(index).map_or(true, |item| {
+                properties.is_limited(item)
+                    || disabled.as_ref().map_or(false, |disabled| disabled(item))
+            })
+        }),
+        text: text
+            .as_ref()
+            .map(|text| -> Box<dyn Fn(usize) -> String + 'cx> {
+                Box::new(move |index| {
+                    items
+                        .get_untracked()
+                        .get(index)
+                        .map(text)
+                        .unwrap_or_default()
+                })
+            }),
+        selected: Box::new(move || {
+            items
+                .get_untracked()
+                .iter()
+                .position(|item| properties.is_selected_untracked(item))
+        }),
+        select: Box::new(move |index| {
+            if let Some(item) = items.get_untracked().get(index) {
+                properties.select_without_toggle(item.clone());
+            }
+        }),
+        scroll_to: Box::new(scroll_to),
+    };
+    // The list is taken out of the slot again before the scope is disposed.
+    let list: Rc<VirtualList<'static>> =
+        Rc::new(unsafe { mem::transmute::<VirtualList<'cx>, VirtualList<'static>>(list) });
+    *slot.0.borrow_mut() = Some(list.clone());
+    on_cleanup(cx, {
+        let slot = slot.clone();
+        move || {
+            slot.0.borrow_mut().take();
+        }
+    });
+
+    let source: Box<dyn Fn() -> Vec<T> + 'cx> = Box::new(move || {
+        items
+            .get_untracked()
+            .iter()
+            .filter(|item| !disabled.as_ref().map_or(false, |disabled| disabled(item)))
+            .cloned()
+            .collect()
+    });
+    properties
+        .options
+        .set_source(Some(unsafe { mem::transmute(source) }));
+    on_cleanup(cx, move || properties.options.set_source(None));
+
+    let listener = create_ref(cx, RefCell::new(None::<(EventTarget, Closure<dyn Fn()>)>));
+    on_cleanup(cx, move || {
+        if let Some((target, callback)) = listener.borrow_mut().take() {
+            let _ = target
+                .remove_event_listener_with_callback("scroll", callback.as_ref().unchecked_ref());
+        }
+    });
+    let attach = move || {
+        let Some((_, container)) = elements() else {
+            return;
+        };
+        let sync: &'static dyn Fn() = as_static(sync);
+        let callback = Closure::<dyn Fn()>::new(move || sync());
+        let _ =
+            container.add_event_listener_with_callback("scroll", callback.as_ref().unchecked_ref());
+        *listener.borrow_mut() = Some((container.into(), callback));
+
+        sync();
+        if let Some(index) = list.selected() {
+            scroll_to(index);
+        }
+    };
+
+    // Measured heights replace the estimates once the rendered items have been laid out.
+    let measure = move || {
+        let (Some(spacer), Some(end)) =
+            (as_html_element(top_spacer), as_html_element(bottom_spacer))
+        else {
+            return;
+        };
+        let start = range.get_untracked().0;
+        let mut heights = HashMap::new();
+        let end: Element = end.into();
+        let mut current = spacer.next_element_sibling();
+        while let Some(node) = current.filter(|node| node != &end) {
+            heights.insert(
+                start + heights.len(),
+                node.get_bounding_client_rect().height(),
+            );
+            current = node.next_element_sibling();
+        }
+        let changed = heights.iter().any(|(index, height)| {
+            measured
+                .get_untracked()
+                .get(index)
+                .map_or(true, |previous| (previous - height).abs() > 0.5)
+        });
+        if changed {
+            measured.modify().extend(heights);
+        }
+    };
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        let attach: Box<dyn Fn() + 'cx> = Box::new(attach);
+        let attach: Box<dyn Fn()> = unsafe { mem::transmute(attach) };
+        let measure: &'static dyn Fn() = as_static(create_ref(cx, measure));
+        let pending = create_ref(cx, RefCell::new((None::<Timeout>, None::<Timeout>)));
+        on_cleanup(cx, move || {
+            let (attaching, measuring) = mem::take(&mut *pending.borrow_mut());
+            attaching.into_iter().chain(measuring).for_each(|timeout| {
+                timeout.cancel();
+            });
+        });
+
+        // Waits for the options to be inserted into the list.
+        pending.borrow_mut().0 = Some(Timeout::new(0, move || attach()));
+        if matches!(item_height, ItemHeight::Measured(_)) {
+            create_effect(cx, move || {
+                range.track();
+                if let Some(timeout) = pending.borrow_mut().1.replace(Timeout::new(0, measure)) {
+                    timeout.cancel();
+                }
+            });
+        }
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    let _ = (attach, measure);
+
+    let top_height = create_memo(cx, move || {
+        let offsets = offsets.get();
+        offsets[range.get().0.min(offsets.len() - 1)]
+    });
+    let bottom_height = create_memo(cx, move || {
+        let offsets = offsets.get();
+        let last = offsets.len() - 1;
+        offsets[last] - offsets[range.get().1.min(last)]
+    });
+
+    view! { cx,
+        li(
+            ref = top_spacer,
+            role = "presentation",
+            aria-hidden = "true",
+            data-sh = "virtual-options-spacer",
+            style = format!("height: {}px; padding: 0; margin: 0; border: 0;", top_height.get()),
+        )
+        Keyed(
+            iterable = visible,
+            view = move |cx, item| view_fn(cx, item),
+            key = move |item: &T| properties.key(item),
+        )
+        li(
+            ref = bottom_spacer,
+            role = "presentation",
+            aria-hidden = "true",
+            data-sh = "virtual-options-spacer",
+            style = format!("height: {}px; padding: 0; margin: 0; border: 0;", bottom_height.get()),
+        )
+    }
+}
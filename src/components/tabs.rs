@@ -9,7 +9,7 @@ use crate::{
     FocusNavigator,
 };
 
-use super::{TransitionContext, TransitionProp};
+use super::{transition_children, PanelTransitionContext, TransitionContext, TransitionProp};
 
 pub struct TabGroupContext {
     owner_id: String,
@@ -248,6 +248,8 @@ pub fn Tab<'cx, G: Html>(cx: Scope<'cx>, props: TabProps<'cx, G>) -> View<G> {
 pub struct TabPanelProps<'cx, G: Html> {
     index: u32,
     transition: Option<TransitionProp<'cx, G>>,
+    #[prop(default = true)]
+    unmount: bool,
     #[prop(default = div.into(), setter(into))]
     element: DynamicElement<'cx, G>,
     #[prop(default, setter(into))]
@@ -263,7 +265,8 @@ pub fn TabPanel<'cx, G: Html>(cx: Scope<'cx>, props: TabPanelProps<'cx, G>) -> V
 
     let show = create_selector(cx, move || *properties.selected_index.get() == props.index);
 
-    let children = props.children.call(cx);
+    let (children, transition_state) =
+        transition_children(cx, props.children, props.transition.is_some());
     let class = class!(cx, props);
 
     let apply_attributes = |element: &G| {
@@ -297,6 +300,13 @@ pub fn TabPanel<'cx, G: Html>(cx: Scope<'cx>, props: TabPanelProps<'cx, G>) -> V
                     node_ref: as_static(node_ref),
                 },
             );
+            provide_context(
+                cx,
+                PanelTransitionContext {
+                    state: transition_state,
+                    unmount: props.unmount,
+                },
+            );
             view = transition(cx, as_static(show));
         });
         let element = node_ref.get_raw();
@@ -307,12 +317,17 @@ pub fn TabPanel<'cx, G: Html>(cx: Scope<'cx>, props: TabPanelProps<'cx, G>) -> V
         let element = view.as_node().unwrap();
         apply_attributes(element);
 
-        view! { cx,
-            (if *show.get() {
-                view.clone()
-            } else {
-                View::empty()
-            })
+        if props.unmount {
+            view! { cx,
+                (if *show.get() {
+                    view.clone()
+                } else {
+                    View::empty()
+                })
+            }
+        } else {
+            element.set_dyn_hidden(cx, move || !*show.get());
+            view
         }
     }
 }
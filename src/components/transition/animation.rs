@@ -0,0 +1,301 @@
+use std::{borrow::Cow, rc::Rc};
+#[cfg(target_arch = "wasm32")]
+use std::{cell::Cell, mem};
+
+#[cfg(target_arch = "wasm32")]
+use js_sys::{Array, Object, Reflect};
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+#[cfg(target_arch = "wasm32")]
+use web_sys::Animation;
+use web_sys::Element;
+
+pub type Keyframe = Vec<(Cow<'static, str>, Cow<'static, str>)>;
+
+#[derive(Clone)]
+pub enum Keyframes {
+    Static(Vec<Keyframe>),
+    Measured(Rc<dyn Fn(&Element) -> Vec<Keyframe>>),
+}
+
+impl Keyframes {
+    pub fn resolve(&self, element: &Element) -> Vec<Keyframe> {
+        match self {
+            Keyframes::Static(keyframes) => keyframes.clone(),
+            Keyframes::Measured(measure) => measure(element),
+        }
+    }
+
+    fn reversed(keyframes: &[Keyframe]) -> Self {
+        Keyframes::Static(keyframes.iter().rev().cloned().collect())
+    }
+}
+
+impl From<Vec<Keyframe>> for Keyframes {
+    fn from(keyframes: Vec<Keyframe>) -> Self {
+        Keyframes::Static(keyframes)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SlideFrom {
+    Top,
+    Right,
+    Bottom,
+    Left,
+}
+
+#[derive(Clone)]
+pub struct TransitionAnimation {
+    pub enter: Keyframes,
+    pub leave: Keyframes,
+    pub duration: f64,
+    pub delay: f64,
+    pub easing: Cow<'static, str>,
+}
+
+fn keyframe(properties: &[(&'static str, &'static str)]) -> Keyframe {
+    properties
+        .iter()
+        .map(|(property, value)| (Cow::Borrowed(*property), Cow::Borrowed(*value)))
+        .collect()
+}
+
+impl TransitionAnimation {
+    pub fn new(enter: impl Into<Keyframes>, leave: impl Into<Keyframes>) -> Self {
+        Self {
+            enter: enter.into(),
+            leave: leave.into(),
+            duration: 150.0,
+            delay: 0.0,
+            easing: "ease-out".into(),
+        }
+    }
+
+    pub fn duration(mut self, duration: f64) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    pub fn delay(mut self, delay: f64) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    pub fn easing(mut self, easing: impl Into<Cow<'static, str>>) -> Self {
+        self.easing = easing.into();
+        self
+    }
+
+    fn symmetric(enter: Vec<Keyframe>) -> Self {
+        let leave = Keyframes::reversed(&enter);
+        Self::new(enter, leave)
+    }
+
+    pub fn fade() -> Self {
+        Self::symmetric(vec![
+            keyframe(&[("opacity", "0")]),
+            keyframe(&[("opacity", "1")]),
+        ])
+    }
+
+    pub fn scale() -> Self {
+        Self::symmetric(vec![
+            keyframe(&[("opacity", "0"), ("transform", "scale(0.95)")]),
+            keyframe(&[("opacity", "1"), ("transform", "scale(1)")]),
+        ])
+    }
+
+    pub fn slide_in(from: SlideFrom) -> Self {
+        let start = match from {
+            SlideFrom::Top => "translateY(-100%)",
+            SlideFrom::Right => "translateX(100%)",
+            SlideFrom::Bottom => "translateY(100%)",
+            SlideFrom::Left => "translateX(-100%)",
+        };
+        Self::symmetric(vec![
+            keyframe(&[("transform", start)]),
+            keyframe(&[("transform", "translate(0, 0)")]),
+        ])
+    }
+
+    pub fn collapse_height() -> Self {
+        // `height: auto` can't be animated, so the height is measured when the phase starts.
+        fn frames(element: &Element) -> Vec<Keyframe> {
+            vec![
+                vec![
+                    ("height".into(), "0px".into()),
+                    ("overflow".into(), "hidden".into()),
+                ],
+                vec![
+                    (
+                        "height".into(),
+                        format!("{}px", element.scroll_height()).into(),
+                    ),
+                    ("overflow".into(), "hidden".into()),
+                ],
+            ]
+        }
+
+        Self::new(
+            Keyframes::Measured(Rc::new(frames)),
+            Keyframes::Measured(Rc::new(|element: &Element| {
+                frames(element).into_iter().rev().collect()
+            })),
+        )
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(method, js_name = animate)]
+    fn animate(this: &Element, keyframes: &Array, options: &Object) -> Animation;
+}
+
+#[cfg(target_arch = "wasm32")]
+fn to_camel_case(property: &str) -> String {
+    let mut parts = property.split('-');
+    let mut camel = parts.next().unwrap_or_default().to_string();
+    for part in parts {
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            camel.extend(first.to_uppercase());
+            camel.push_str(chars.as_str());
+        }
+    }
+    camel
+}
+
+#[cfg(target_arch = "wasm32")]
+pub(super) fn run_animation<'a>(
+    element: &Element,
+    animation: &TransitionAnimation,
+    entering: bool,
+    done: Rc<Cell<bool>>,
+    end: impl FnOnce() + 'a,
+) -> Animation {
+    let keyframes = if entering {
+        &animation.enter
+    } else {
+        &animation.leave
+    };
+    let keyframes = keyframes
+        .resolve(element)
+        .iter()
+        .map(|keyframe| {
+            let object = Object::new();
+            for (property, value) in keyframe {
+                let _ = Reflect::set(
+                    &object,
+                    &to_camel_case(property).into(),
+                    &JsValue::from_str(value),
+                );
+            }
+            JsValue::from(object)
+        })
+        .collect::<Array>();
+
+    let options = Object::new();
+    let _ = Reflect::set(&options, &"duration".into(), &animation.duration.into());
+    let _ = Reflect::set(&options, &"delay".into(), &animation.delay.into());
+    let _ = Reflect::set(
+        &options,
+        &"easing".into(),
+        &JsValue::from_str(&animation.easing),
+    );
+    // Leaving keeps the final frame applied until the element is unmounted.
+    let fill = if entering { "backwards" } else { "forwards" };
+    let _ = Reflect::set(&options, &"fill".into(), &fill.into());
+
+    let handle = element.animate(&keyframes, &options);
+
+    let Ok(finished) = handle.finished() else {
+        done.set(true);
+        end();
+        return handle;
+    };
+    let end: Box<dyn FnOnce() + 'a> = Box::new(end);
+    let end: Box<dyn FnOnce() + 'static> = unsafe { mem::transmute(end) };
+    spawn_local(async move {
+        // `finished` rejects when the animation is cancelled. A newer phase has already marked
+        // this one done, any other cancellation still ends the phase.
+        let _ = JsFuture::from(finished).await;
+        if !done.get() {
+            done.set(true);
+            end();
+        }
+    });
+
+    handle
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Keyframe, Keyframes, SlideFrom, TransitionAnimation};
+
+    fn frames(keyframes: &Keyframes) -> &[Keyframe] {
+        match keyframes {
+            Keyframes::Static(frames) => frames,
+            Keyframes::Measured(_) => panic!("expected static keyframes"),
+        }
+    }
+
+    fn value<'a>(keyframe: &'a Keyframe, property: &str) -> Option<&'a str> {
+        keyframe
+            .iter()
+            .find(|(name, _)| name == property)
+            .map(|(_, value)| value.as_ref())
+    }
+
+    #[test]
+    pub fn test_presets_leave_in_reverse() {
+        for animation in [
+            TransitionAnimation::fade(),
+            TransitionAnimation::scale(),
+            TransitionAnimation::slide_in(SlideFrom::Left),
+        ] {
+            let enter = frames(&animation.enter);
+            let leave = frames(&animation.leave);
+            assert_eq!(enter.len(), 2);
+            assert_eq!(leave, enter.iter().rev().cloned().collect::<Vec<_>>());
+        }
+
+        let fade = TransitionAnimation::fade();
+        assert_eq!(value(&frames(&fade.enter)[0], "opacity"), Some("0"));
+        assert_eq!(value(&frames(&fade.enter)[1], "opacity"), Some("1"));
+    }
+
+    #[test]
+    pub fn test_slide_in_starts_off_the_given_edge() {
+        for (from, start) in [
+            (SlideFrom::Top, "translateY(-100%)"),
+            (SlideFrom::Right, "translateX(100%)"),
+            (SlideFrom::Bottom, "translateY(100%)"),
+            (SlideFrom::Left, "translateX(-100%)"),
+        ] {
+            let animation = TransitionAnimation::slide_in(from);
+            assert_eq!(
+                value(&frames(&animation.enter)[0], "transform"),
+                Some(start)
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_builder_overrides_timing() {
+        let animation = TransitionAnimation::fade()
+            .duration(300.0)
+            .delay(50.0)
+            .easing("linear");
+        assert_eq!(animation.duration, 300.0);
+        assert_eq!(animation.delay, 50.0);
+        assert_eq!(animation.easing, "linear");
+        assert!(matches!(
+            TransitionAnimation::collapse_height().enter,
+            Keyframes::Measured(_)
+        ));
+    }
+}
@@ -0,0 +1,569 @@
+use std::{borrow::Cow, hash::Hash};
+#[cfg(target_arch = "wasm32")]
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::Rc,
+};
+
+use sycamore::{builder::prelude::div, prelude::*};
+use sycamore_utils::{DynamicElement, ReactiveStr};
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::JsCast;
+#[cfg(target_arch = "wasm32")]
+use web_sys::{Animation, Element, HtmlElement, Node};
+
+#[cfg(target_arch = "wasm32")]
+use super::{
+    add_class, dom_node, next_frame, next_phase, on_transition_end, remove_class, run_animation,
+    set_shortened, shortened, use_motion, Keyframes, Motion,
+};
+use super::{ReducedMotionPolicy, TransitionAnimation};
+use crate::utils::{get_ref, SetDynAttr};
+
+#[allow(unused)]
+#[derive(Props)]
+pub struct TransitionGroupProps<'cx, T: 'static, K: 'static, F, KF, G: Html>
+where
+    F: Fn(BoundedScope<'_, 'cx>, T) -> View<G> + 'cx,
+    KF: Fn(&T) -> K + 'cx,
+{
+    iterable: &'cx ReadSignal<Vec<T>>,
+    view: F,
+    key: KF,
+
+    #[prop(default, setter(into))]
+    enter: Cow<'static, str>,
+    #[prop(default, setter(into))]
+    enter_from: Cow<'static, str>,
+    #[prop(default, setter(into))]
+    enter_to: Cow<'static, str>,
+    #[prop(default)]
+    appear: bool,
+    #[prop(default, setter(into))]
+    leave: Cow<'static, str>,
+    #[prop(default, setter(into))]
+    leave_from: Cow<'static, str>,
+    #[prop(default, setter(into))]
+    leave_to: Cow<'static, str>,
+    #[prop(default, setter(into))]
+    move_class: Cow<'static, str>,
+    #[prop(default = 200.0)]
+    move_duration: f64,
+    #[prop(default)]
+    stagger: u32,
+    animation: Option<TransitionAnimation>,
+    reduced_motion: Option<ReducedMotionPolicy>,
+
+    #[prop(default = div.into(), setter(into))]
+    element: DynamicElement<'cx, G>,
+    #[prop(default, setter(into))]
+    class: ReactiveStr<'cx>,
+    attributes: Attributes<'cx, G>,
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), allow(unused))]
+struct GroupProperties {
+    enter: Cow<'static, str>,
+    enter_from: Cow<'static, str>,
+    enter_to: Cow<'static, str>,
+    leave: Cow<'static, str>,
+    leave_from: Cow<'static, str>,
+    leave_to: Cow<'static, str>,
+    move_class: Cow<'static, str>,
+    move_duration: f64,
+    stagger: u32,
+    animation: Option<TransitionAnimation>,
+}
+
+#[derive(Clone)]
+struct GroupEntry<T, K> {
+    key: K,
+    value: T,
+    leaving: RcSignal<bool>,
+    delay: RcSignal<u32>,
+    appear: bool,
+}
+
+impl<T, K: PartialEq> PartialEq for GroupEntry<T, K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl<T, K: Eq> Eq for GroupEntry<T, K> {}
+
+#[cfg(target_arch = "wasm32")]
+fn set_delay(node: &Node, delay: u32) {
+    let style = node.unchecked_ref::<HtmlElement>().style();
+    for property in ["transition-delay", "animation-delay"] {
+        if delay > 0 {
+            let _ = style.set_property(property, &format!("{delay}ms"));
+        } else {
+            let _ = style.remove_property(property);
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn measure<K: Clone + Hash + Eq>(nodes: &RefCell<HashMap<K, DomNode>>) -> HashMap<K, (f64, f64)> {
+    nodes
+        .borrow()
+        .iter()
+        .map(|(key, node)| {
+            let rect = node
+                .to_web_sys()
+                .unchecked_ref::<Element>()
+                .get_bounding_client_rect();
+            (key.clone(), (rect.left(), rect.top()))
+        })
+        .collect()
+}
+
+// FLIP: items that moved are translated back to where they were and animated into place.
+#[cfg(target_arch = "wasm32")]
+fn play_moves<'a, K: Clone + Hash + Eq>(
+    nodes: &RefCell<HashMap<K, DomNode>>,
+    before: HashMap<K, (f64, f64)>,
+    properties: &'a GroupProperties,
+) {
+    for (key, node) in nodes.borrow().iter() {
+        let Some((left, top)) = before.get(key) else {
+            continue;
+        };
+        let element: Element = node.to_web_sys().unchecked_into();
+        let rect = element.get_bounding_client_rect();
+        let (dx, dy) = (left - rect.left(), top - rect.top());
+        if dx.abs() < 0.5 && dy.abs() < 0.5 {
+            continue;
+        }
+
+        let animation = TransitionAnimation::new(
+            vec![
+                vec![(
+                    "transform".into(),
+                    format!("translate({dx}px, {dy}px)").into(),
+                )],
+                vec![("transform".into(), "none".into())],
+            ],
+            Keyframes::Static(Vec::new()),
+        )
+        .duration(properties.move_duration)
+        .easing(
+            properties
+                .animation
+                .as_ref()
+                .map_or("ease".into(), |animation| animation.easing.clone()),
+        );
+
+        add_class(&element, &properties.move_class);
+        let moved = element.clone();
+        run_animation(
+            &element,
+            &animation,
+            true,
+            Rc::new(Cell::new(false)),
+            move || remove_class(&moved, &properties.move_class),
+        );
+    }
+}
+
+fn keep_removed<T: Clone, K: Clone + PartialEq>(
+    previous: &[GroupEntry<T, K>],
+    next: &mut Vec<GroupEntry<T, K>>,
+) -> Vec<GroupEntry<T, K>> {
+    let mut removed = Vec::new();
+    for (index, entry) in previous.iter().enumerate() {
+        if next.iter().any(|next| next.key == entry.key) {
+            continue;
+        }
+        let position = previous[..index]
+            .iter()
+            .rev()
+            .find_map(|before| next.iter().position(|next| next.key == before.key))
+            .map_or(0, |position| position + 1);
+        next.insert(position, entry.clone());
+        removed.push(entry.clone());
+    }
+    removed
+}
+
+struct GroupUpdate<T, K> {
+    entries: Vec<GroupEntry<T, K>>,
+    // Removed entries that start leaving now, with their stagger delay.
+    leaving: Vec<(GroupEntry<T, K>, u32)>,
+    // Leaving entries that were added back.
+    resumed: Vec<GroupEntry<T, K>>,
+}
+
+// Entering and leaving items are each staggered one after another, in list order.
+fn update_entries<T: Clone, K: Clone + PartialEq>(
+    previous: &[GroupEntry<T, K>],
+    items: &[T],
+    key_fn: impl Fn(&T) -> K,
+    stagger: u32,
+    appear: bool,
+    keep_leaving: bool,
+) -> GroupUpdate<T, K> {
+    let mut entering = 0;
+    let mut entries = items
+        .iter()
+        .map(|item| {
+            let key = key_fn(item);
+            match previous.iter().find(|entry| entry.key == key) {
+                Some(entry) => GroupEntry {
+                    value: item.clone(),
+                    ..entry.clone()
+                },
+                None => {
+                    let delay = entering * stagger;
+                    entering += 1;
+                    GroupEntry {
+                        key,
+                        value: item.clone(),
+                        leaving: create_rc_signal(false),
+                        delay: create_rc_signal(delay),
+                        appear,
+                    }
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // Removed items stay at their previous position until their leave transition ends.
+    let removed = if keep_leaving {
+        keep_removed(previous, &mut entries)
+    } else {
+        Vec::new()
+    };
+    let resumed = entries
+        .iter()
+        .filter(|entry| *entry.leaving.get_untracked() && !removed.contains(entry))
+        .cloned()
+        .collect();
+    let leaving = removed
+        .into_iter()
+        .enumerate()
+        .filter(|(_, entry)| !*entry.leaving.get_untracked())
+        .map(|(index, entry)| (entry, index as u32 * stagger))
+        .collect();
+
+    GroupUpdate {
+        entries,
+        leaving,
+        resumed,
+    }
+}
+
+#[component]
+pub fn TransitionGroup<'cx, T, K, F, KF, G: Html>(
+    cx: Scope<'cx>,
+    props: TransitionGroupProps<'cx, T, K, F, KF, G>,
+) -> View<G>
+where
+    T: Clone + 'static,
+    K: Clone + Hash + Eq + 'static,
+    F: Fn(BoundedScope<'_, 'cx>, T) -> View<G> + 'cx,
+    KF: Fn(&T) -> K + 'cx,
+{
+    let view_fn = create_ref(cx, props.view);
+    let key_fn = create_ref(cx, props.key);
+    #[cfg_attr(not(target_arch = "wasm32"), allow(unused))]
+    let properties = create_ref(
+        cx,
+        GroupProperties {
+            enter: props.enter,
+            enter_from: props.enter_from,
+            enter_to: props.enter_to,
+            leave: props.leave,
+            leave_from: props.leave_from,
+            leave_to: props.leave_to,
+            move_class: props.move_class,
+            move_duration: props.move_duration,
+            stagger: props.stagger,
+            animation: props.animation,
+        },
+    );
+    let entries = create_signal(cx, Vec::<GroupEntry<T, K>>::new());
+
+    #[cfg(target_arch = "wasm32")]
+    let nodes = create_ref(cx, RefCell::new(HashMap::<K, DomNode>::new()));
+    #[cfg(target_arch = "wasm32")]
+    let motion = use_motion(cx, props.reduced_motion);
+
+    let mut initial = true;
+    let appear = props.appear;
+    create_effect(cx, move || {
+        let items = props.iterable.get();
+        let previous = entries.get_untracked();
+
+        #[cfg(target_arch = "wasm32")]
+        let before = (*motion.get_untracked() != Motion::Skipped).then(|| measure(nodes));
+
+        // Without a DOM there is nothing to animate, so removed items go right away.
+        let update = update_entries(
+            &previous,
+            &items,
+            key_fn,
+            properties.stagger,
+            appear || !initial,
+            cfg!(target_arch = "wasm32"),
+        );
+        initial = false;
+        entries.set(update.entries);
+
+        for (entry, delay) in update.leaving {
+            entry.delay.set(delay);
+            entry.leaving.set(true);
+        }
+        for entry in update.resumed {
+            entry.delay.set(0);
+            entry.leaving.set(false);
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        if let Some(before) = before {
+            play_moves(nodes, before, properties);
+        }
+    });
+
+    #[cfg_attr(not(target_arch = "wasm32"), allow(unused))]
+    let remove = move |key: &K| {
+        entries.modify().retain(|entry| {
+            // The item may have been added back while it was leaving.
+            &entry.key != key || !*entry.leaving.get_untracked()
+        });
+    };
+
+    let render = move |cx: BoundedScope<'_, 'cx>, entry: GroupEntry<T, K>| {
+        let view = view_fn(cx, entry.value.clone());
+
+        #[cfg(target_arch = "wasm32")]
+        if let Some(node) = view.as_node() {
+            let node_ref = create_node_ref(cx);
+            node_ref.set(node.clone());
+            if let Some((node, window)) = dom_node(node_ref).zip(web_sys::window()) {
+                let key = entry.key.clone();
+                nodes.borrow_mut().insert(key.clone(), node.clone());
+                on_cleanup(cx, {
+                    let key = key.clone();
+                    move || {
+                        nodes.borrow_mut().remove(&key);
+                    }
+                });
+
+                let phase = create_ref(cx, RefCell::new(Rc::new(Cell::new(true))));
+                let current_animation = create_ref(cx, RefCell::new(None::<Animation>));
+                let element = create_ref(cx, node);
+                let key = create_ref(cx, key);
+                let window = create_ref(cx, window);
+
+                on_cleanup(cx, move || {
+                    if let Some(animation) = current_animation.borrow_mut().take() {
+                        animation.cancel();
+                    }
+                    next_phase(phase);
+                });
+
+                let run = create_ref(cx, move |entering: bool, delay: u32| {
+                    if let Some(animation) = current_animation.borrow_mut().take() {
+                        animation.cancel();
+                    }
+                    let done = next_phase(phase);
+                    let motion = *motion.get_untracked();
+                    let (active, from, to) = if entering {
+                        (
+                            &properties.enter,
+                            &properties.enter_from,
+                            &properties.enter_to,
+                        )
+                    } else {
+                        (
+                            &properties.leave,
+                            &properties.leave_from,
+                            &properties.leave_to,
+                        )
+                    };
+                    let node = element.to_web_sys();
+
+                    let end = move || {
+                        let node = element.to_web_sys();
+                        if motion == Motion::Shortened {
+                            set_shortened(&node, false);
+                        }
+                        set_delay(&node, 0);
+                        remove_class(&node, active);
+                        remove_class(&node, to);
+                        if !entering {
+                            remove(key);
+                        }
+                    };
+
+                    if motion == Motion::Skipped {
+                        done.set(true);
+                        end();
+                        return;
+                    }
+
+                    if let Some(animation) = properties.animation.as_ref() {
+                        let animation = shortened(animation, motion).into_owned();
+                        *current_animation.borrow_mut() = Some(run_animation(
+                            node.unchecked_ref::<Element>(),
+                            &animation.delay(delay as f64),
+                            entering,
+                            done,
+                            end,
+                        ));
+                        return;
+                    }
+
+                    if active.is_empty() && from.is_empty() && to.is_empty() {
+                        done.set(true);
+                        end();
+                        return;
+                    }
+
+                    if motion == Motion::Shortened {
+                        set_shortened(&node, true);
+                    }
+                    set_delay(&node, delay);
+                    // A reversed item may still carry the classes of the interrupted phase.
+                    for class in [
+                        &properties.enter,
+                        &properties.enter_from,
+                        &properties.enter_to,
+                        &properties.leave,
+                        &properties.leave_from,
+                        &properties.leave_to,
+                    ] {
+                        remove_class(&node, class);
+                    }
+                    add_class(&node, active);
+                    add_class(&node, from);
+                    next_frame(window, move || {
+                        remove_class(&node, from);
+                        add_class(&node, to);
+                    });
+                    on_transition_end(element, done, end);
+                });
+
+                let mut first = true;
+                create_effect(cx, move || {
+                    let leaving = *entry.leaving.get();
+                    let delay = *entry.delay.get_untracked();
+                    if first {
+                        first = false;
+                        if entry.appear && !leaving {
+                            // Entering starts once the item is attached.
+                            next_frame(window, move || run(true, delay));
+                        }
+                        return;
+                    }
+                    run(!leaving, delay);
+                });
+            }
+        }
+
+        view
+    };
+
+    let node = get_ref(cx, &props.attributes);
+    let class = class!(cx, props);
+    let view = props.element.call(cx);
+    let element = view.as_node().unwrap();
+    node.set(element.clone());
+
+    let children = view! { cx,
+        Keyed(
+            iterable = entries,
+            view = render,
+            key = |entry: &GroupEntry<T, K>| entry.key.clone(),
+        )
+    };
+
+    element.set_class_name((*class.get()).clone().into());
+    element.set_children(cx, children);
+    element.apply_attributes(cx, &props.attributes);
+
+    view
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{keep_removed, update_entries, GroupEntry, GroupUpdate};
+
+    fn keys(entries: &[GroupEntry<u32, u32>]) -> Vec<u32> {
+        entries.iter().map(|entry| entry.key).collect()
+    }
+
+    fn update(previous: &[GroupEntry<u32, u32>], items: &[u32]) -> GroupUpdate<u32, u32> {
+        update_entries(previous, items, |item| *item, 50, true, true)
+    }
+
+    #[test]
+    pub fn test_keep_removed_keeps_previous_position() {
+        let previous = update(&[], &[1, 2, 3, 4]).entries;
+        let without_removed =
+            |items: &[u32]| update_entries(&previous, items, |item| *item, 0, true, false).entries;
+
+        let mut next = without_removed(&[1, 4]);
+        let removed = keep_removed(&previous, &mut next);
+        assert_eq!(keys(&removed), vec![2, 3]);
+        assert_eq!(keys(&next), vec![1, 2, 3, 4]);
+
+        // Removed items follow the item they came after.
+        let mut next = without_removed(&[4, 1]);
+        keep_removed(&previous, &mut next);
+        assert_eq!(keys(&next), vec![4, 1, 2, 3]);
+
+        let mut next = without_removed(&[3]);
+        keep_removed(&previous, &mut next);
+        assert_eq!(keys(&next), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    pub fn test_entering_items_are_staggered() {
+        let first = update(&[], &[1, 2]);
+        let delays = |entries: &[GroupEntry<u32, u32>]| {
+            entries
+                .iter()
+                .map(|entry| *entry.delay.get_untracked())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(delays(&first.entries), vec![0, 50]);
+
+        // Only new items count towards the stagger.
+        let second = update(&first.entries, &[1, 3, 2, 4]);
+        assert_eq!(keys(&second.entries), vec![1, 3, 2, 4]);
+        assert_eq!(delays(&second.entries), vec![0, 0, 50, 50]);
+    }
+
+    #[test]
+    pub fn test_leaving_items_are_staggered_and_resumed() {
+        let previous = update(&[], &[1, 2, 3]).entries;
+
+        let removed = update(&previous, &[2]);
+        assert_eq!(keys(&removed.entries), vec![1, 2, 3]);
+        let leaving = removed
+            .leaving
+            .iter()
+            .map(|(entry, delay)| (entry.key, *delay))
+            .collect::<Vec<_>>();
+        assert_eq!(leaving, vec![(1, 0), (3, 50)]);
+
+        for (entry, _) in &removed.leaving {
+            entry.leaving.set(true);
+        }
+        let readded = update(&removed.entries, &[1, 2]);
+        assert_eq!(keys(&readded.resumed), vec![1]);
+        assert_eq!(
+            readded
+                .leaving
+                .iter()
+                .map(|(entry, _)| entry.key)
+                .collect::<Vec<_>>(),
+            Vec::<u32>::new()
+        );
+        assert_eq!(keys(&readded.entries), vec![1, 2, 3]);
+    }
+}
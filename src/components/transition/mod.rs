@@ -0,0 +1,833 @@
+use std::{borrow::Cow, cell::Cell};
+#[cfg(target_arch = "wasm32")]
+use std::{cell::RefCell, mem, rc::Rc};
+
+mod animation;
+mod group;
+mod reduced_motion;
+mod state;
+
+pub use animation::*;
+pub use group::*;
+pub use reduced_motion::*;
+pub use state::*;
+
+#[cfg(target_arch = "wasm32")]
+use crate::utils::oneshot_event;
+use crate::{
+    hooks::create_id,
+    utils::{as_static, get_ref, scoped_children, SetDynAttr},
+};
+#[cfg(target_arch = "wasm32")]
+use js_sys::Array;
+#[cfg(target_arch = "wasm32")]
+use sycamore::web::html::ev;
+use sycamore::{builder::prelude::div, prelude::*};
+use sycamore_utils::{DynamicElement, ReactiveBool, ReactiveStr};
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::Closure;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::{JsCast, JsValue, UnwrapThrowExt};
+#[cfg(target_arch = "wasm32")]
+use web_sys::Window;
+#[cfg(target_arch = "wasm32")]
+use web_sys::{
+    Animation, AnimationEvent, Element, Event, EventTarget, HtmlElement, Node, TransitionEvent,
+};
+
+pub type TransitionProp<'cx, G> =
+    Box<dyn FnOnce(BoundedScope<'_, 'cx>, &'cx ReadSignal<bool>) -> View<G> + 'cx>;
+
+#[allow(unused)]
+#[derive(Props)]
+pub struct TransitionProps<'cx, G: Html> {
+    #[prop(setter(into))]
+    show: ReactiveBool<'cx>,
+
+    #[prop(default, setter(into))]
+    enter: Cow<'static, str>,
+    #[prop(default, setter(into))]
+    enter_from: Cow<'static, str>,
+    #[prop(default, setter(into))]
+    enter_to: Cow<'static, str>,
+    #[prop(default, setter(into))]
+    entered: Cow<'static, str>,
+    #[prop(default)]
+    appear: bool,
+    #[prop(default, setter(into))]
+    appear_active: Cow<'static, str>,
+    #[prop(default, setter(into))]
+    appear_from: Cow<'static, str>,
+    #[prop(default, setter(into))]
+    appear_to: Cow<'static, str>,
+    #[prop(default, setter(into))]
+    leave: Cow<'static, str>,
+    #[prop(default, setter(into))]
+    leave_from: Cow<'static, str>,
+    #[prop(default, setter(into))]
+    leave_to: Cow<'static, str>,
+    animation: Option<TransitionAnimation>,
+    reduced_motion: Option<ReducedMotionPolicy>,
+    #[prop(default = true)]
+    unmount: bool,
+
+    #[prop(setter(into))]
+    before_enter: Option<Box<dyn Fn()>>,
+    #[prop(setter(into))]
+    after_enter: Option<Box<dyn Fn()>>,
+    #[prop(setter(into))]
+    before_leave: Option<Box<dyn Fn()>>,
+    #[prop(setter(into))]
+    after_leave: Option<Box<dyn Fn()>>,
+
+    #[prop(default = div.into(), setter(into))]
+    element: DynamicElement<'cx, G>,
+    #[prop(default, setter(into))]
+    class: ReactiveStr<'cx>,
+    children: Children<'cx, G>,
+    attributes: Attributes<'cx, G>,
+}
+
+#[allow(unused)]
+#[derive(Props)]
+pub struct TransitionChildProps<'cx, G: Html> {
+    #[prop(default, setter(into))]
+    enter: Cow<'static, str>,
+    #[prop(default, setter(into))]
+    enter_from: Cow<'static, str>,
+    #[prop(default, setter(into))]
+    enter_to: Cow<'static, str>,
+    #[prop(default, setter(into))]
+    entered: Cow<'static, str>,
+    #[prop(default)]
+    appear: bool,
+    #[prop(default, setter(into))]
+    appear_active: Cow<'static, str>,
+    #[prop(default, setter(into))]
+    appear_from: Cow<'static, str>,
+    #[prop(default, setter(into))]
+    appear_to: Cow<'static, str>,
+    #[prop(default, setter(into))]
+    leave: Cow<'static, str>,
+    #[prop(default, setter(into))]
+    leave_from: Cow<'static, str>,
+    #[prop(default, setter(into))]
+    leave_to: Cow<'static, str>,
+    animation: Option<TransitionAnimation>,
+    reduced_motion: Option<ReducedMotionPolicy>,
+    #[prop(default = true)]
+    unmount: bool,
+
+    #[prop(setter(into))]
+    before_enter: Option<Box<dyn Fn()>>,
+    #[prop(setter(into))]
+    after_enter: Option<Box<dyn Fn()>>,
+    #[prop(setter(into))]
+    before_leave: Option<Box<dyn Fn()>>,
+    #[prop(setter(into))]
+    after_leave: Option<Box<dyn Fn()>>,
+
+    #[prop(default = div.into(), setter(into))]
+    element: DynamicElement<'cx, G>,
+    #[prop(default, setter(into))]
+    class: ReactiveStr<'cx>,
+    children: Children<'cx, G>,
+    attributes: Attributes<'cx, G>,
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), allow(unused))]
+struct Properties {
+    enter: Cow<'static, str>,
+    enter_from: Cow<'static, str>,
+    enter_to: Cow<'static, str>,
+    entered: Cow<'static, str>,
+    appear: bool,
+    appear_active: Cow<'static, str>,
+    appear_from: Cow<'static, str>,
+    appear_to: Cow<'static, str>,
+    leave: Cow<'static, str>,
+    leave_from: Cow<'static, str>,
+    leave_to: Cow<'static, str>,
+    animation: Option<TransitionAnimation>,
+    reduced_motion: Option<ReducedMotionPolicy>,
+
+    before_enter: Option<Box<dyn Fn()>>,
+    after_enter: Option<Box<dyn Fn()>>,
+    before_leave: Option<Box<dyn Fn()>>,
+    after_leave: Option<Box<dyn Fn()>>,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Properties {
+    fn enter_classes(&self, appear: bool) -> (&str, &str, &str) {
+        let has_appear = !(self.appear_active.is_empty()
+            && self.appear_from.is_empty()
+            && self.appear_to.is_empty());
+        if appear && has_appear {
+            (&self.appear_active, &self.appear_from, &self.appear_to)
+        } else {
+            (&self.enter, &self.enter_from, &self.enter_to)
+        }
+    }
+
+    fn has_leave(&self) -> bool {
+        !(self.leave.is_empty() && self.leave_from.is_empty() && self.leave_to.is_empty())
+    }
+}
+
+struct TransitionParts<'cx, G: Html> {
+    show: ReactiveBool<'cx>,
+    unmount: bool,
+    properties: Properties,
+    element: DynamicElement<'cx, G>,
+    class: ReactiveStr<'cx>,
+    children: Children<'cx, G>,
+    attributes: Attributes<'cx, G>,
+}
+
+impl<'cx, G: Html> From<TransitionProps<'cx, G>> for TransitionParts<'cx, G> {
+    fn from(props: TransitionProps<'cx, G>) -> Self {
+        Self {
+            show: props.show,
+            unmount: props.unmount,
+            properties: Properties {
+                enter: props.enter,
+                enter_from: props.enter_from,
+                enter_to: props.enter_to,
+                entered: props.entered,
+                appear: props.appear,
+                appear_active: props.appear_active,
+                appear_from: props.appear_from,
+                appear_to: props.appear_to,
+                leave: props.leave,
+                leave_from: props.leave_from,
+                leave_to: props.leave_to,
+                animation: props.animation,
+                reduced_motion: props.reduced_motion,
+
+                before_enter: props.before_enter,
+                after_enter: props.after_enter,
+                before_leave: props.before_leave,
+                after_leave: props.after_leave,
+            },
+            element: props.element,
+            class: props.class,
+            children: props.children,
+            attributes: props.attributes,
+        }
+    }
+}
+
+pub struct TransitionContext<G: Html> {
+    pub node_ref: &'static NodeRef<G>,
+}
+
+#[derive(Clone)]
+pub struct TransitionRootContext {
+    show: &'static ReadSignal<bool>,
+    visible_children: &'static Signal<u32>,
+}
+
+impl TransitionRootContext {
+    fn register_child(&self) {
+        self.visible_children
+            .set(*self.visible_children.get_untracked() + 1);
+    }
+
+    fn unregister_child(&self) {
+        self.visible_children
+            .set(self.visible_children.get_untracked().saturating_sub(1));
+    }
+}
+
+#[derive(Clone, Copy)]
+enum TransitionRole {
+    Standalone,
+    Root(&'static TransitionRootContext),
+    Child(&'static TransitionRootContext),
+}
+
+#[cfg(target_arch = "wasm32")]
+fn add_class(node: &Node, class: &str) {
+    let class_list = class.split_ascii_whitespace().collect::<Vec<_>>();
+    if class_list.len() == 1 {
+        node.unchecked_ref::<Element>()
+            .class_list()
+            .add_1(class_list[0])
+            .unwrap_throw();
+    } else {
+        node.unchecked_ref::<Element>()
+            .class_list()
+            .add(&class_list.into_iter().map(JsValue::from).collect::<Array>())
+            .unwrap_throw();
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn remove_class(node: &Node, class: &str) {
+    let class_list = class.split_ascii_whitespace().collect::<Vec<_>>();
+    if class_list.len() == 1 {
+        node.unchecked_ref::<Element>()
+            .class_list()
+            .remove_1(class_list[0])
+            .unwrap_throw();
+    } else {
+        node.unchecked_ref::<Element>()
+            .class_list()
+            .remove(&class_list.into_iter().map(JsValue::from).collect::<Array>())
+            .unwrap_throw();
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+const SHORTENED_DURATION_MS: f64 = 0.01;
+
+// Shortened transitions still run so `transitionend` fires and the end classes apply.
+#[cfg(target_arch = "wasm32")]
+fn set_shortened(node: &Node, shortened: bool) {
+    let style = node.unchecked_ref::<HtmlElement>().style();
+    for property in ["transition-duration", "animation-duration"] {
+        if shortened {
+            let _ = style.set_property(property, &format!("{SHORTENED_DURATION_MS}ms"));
+        } else {
+            let _ = style.remove_property(property);
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn shortened(animation: &TransitionAnimation, motion: Motion) -> Cow<'_, TransitionAnimation> {
+    if motion == Motion::Shortened {
+        Cow::Owned(animation.clone().duration(SHORTENED_DURATION_MS))
+    } else {
+        Cow::Borrowed(animation)
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn next_frame<'a>(window: &Window, f: impl FnOnce() + 'a) {
+    let closure = Rc::new(RefCell::new(None));
+    let c = closure.clone();
+    let mut f = Some(f);
+
+    let boxed: Box<dyn FnMut() + 'a> = Box::new(move || {
+        if let Some(f) = f.take() {
+            f();
+        }
+        let _ = c.borrow_mut().take();
+    });
+
+    *closure.borrow_mut() = Some(Closure::<dyn FnMut()>::new::<Box<dyn FnMut() + 'static>>(
+        unsafe { mem::transmute(boxed) },
+    ));
+
+    let _ =
+        window.request_animation_frame(closure.borrow().as_ref().unwrap().as_ref().unchecked_ref());
+}
+
+// Hydrated elements are `HydrateNode`s, so the transition works on the underlying DOM node.
+#[cfg(target_arch = "wasm32")]
+fn dom_node<G: Html>(node: &NodeRef<G>) -> Option<DomNode> {
+    if !G::IS_BROWSER {
+        return None;
+    }
+    node.try_get_raw()
+        .map(|node| DomNode::from_web_sys(node.to_web_sys()))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn next_phase(phase: &RefCell<Rc<Cell<bool>>>) -> Rc<Cell<bool>> {
+    let next = Rc::new(Cell::new(false));
+    phase.replace(next.clone()).set(true);
+    next
+}
+
+// Transition events bubble, so events from nested transitions need to be filtered out.
+#[cfg(target_arch = "wasm32")]
+fn on_transition_end<'a>(element: &DomNode, done: Rc<Cell<bool>>, end: impl Fn() + 'a) {
+    let end: Rc<dyn Fn() + 'a> = Rc::new(end);
+    once_own_end(element, false, done.clone(), end.clone());
+    once_own_end(element, true, done, end);
+}
+
+#[cfg(target_arch = "wasm32")]
+fn once_own_end<'a>(
+    element: &DomNode,
+    animation: bool,
+    done: Rc<Cell<bool>>,
+    end: Rc<dyn Fn() + 'a>,
+) {
+    let target = element.clone();
+    let handler = move |e: &Event| {
+        if done.get() {
+            return;
+        }
+        if e.target().as_ref() == Some(target.to_web_sys().unchecked_ref::<EventTarget>()) {
+            done.set(true);
+            end();
+        } else {
+            once_own_end(&target, animation, done.clone(), end.clone());
+        }
+    };
+
+    if animation {
+        oneshot_event(element, ev::animationend, move |e: AnimationEvent| {
+            handler(&e)
+        });
+    } else {
+        oneshot_event(element, ev::transitionend, move |e: TransitionEvent| {
+            handler(&e)
+        });
+    }
+}
+
+#[component]
+pub fn Transition<'cx, G: Html>(cx: Scope<'cx>, props: TransitionProps<'cx, G>) -> View<G> {
+    render_transition(cx, props.into(), TransitionRole::Standalone)
+}
+
+#[component]
+pub fn TransitionRoot<'cx, G: Html>(cx: Scope<'cx>, props: TransitionProps<'cx, G>) -> View<G> {
+    let show = props.show.clone();
+    let context = create_ref(
+        cx,
+        TransitionRootContext {
+            show: as_static(create_memo(cx, move || show.get())),
+            visible_children: as_static(create_signal(cx, 0)),
+        },
+    );
+
+    render_transition(cx, props.into(), TransitionRole::Root(as_static(context)))
+}
+
+#[component]
+pub fn TransitionChild<'cx, G: Html>(
+    cx: Scope<'cx>,
+    props: TransitionChildProps<'cx, G>,
+) -> View<G> {
+    let root: &TransitionRootContext = as_static(use_context(cx));
+
+    let parts = TransitionParts {
+        show: root.show.into(),
+        unmount: props.unmount,
+        properties: Properties {
+            enter: props.enter,
+            enter_from: props.enter_from,
+            enter_to: props.enter_to,
+            entered: props.entered,
+            appear: props.appear,
+            appear_active: props.appear_active,
+            appear_from: props.appear_from,
+            appear_to: props.appear_to,
+            leave: props.leave,
+            leave_from: props.leave_from,
+            leave_to: props.leave_to,
+            animation: props.animation,
+            reduced_motion: props.reduced_motion,
+
+            before_enter: props.before_enter,
+            after_enter: props.after_enter,
+            before_leave: props.before_leave,
+            after_leave: props.after_leave,
+        },
+        element: props.element,
+        class: props.class,
+        children: props.children,
+        attributes: props.attributes,
+    };
+
+    render_transition(cx, parts, TransitionRole::Child(root))
+}
+
+fn render_transition<'cx, G: Html>(
+    cx: Scope<'cx>,
+    parts: TransitionParts<'cx, G>,
+    role: TransitionRole,
+) -> View<G> {
+    let visible = create_signal(cx, parts.show.get());
+    #[cfg(target_arch = "wasm32")]
+    let appear = parts.properties.appear && parts.show.get();
+    let left = create_signal(cx, false);
+    let properties = create_ref(cx, parts.properties);
+
+    // Transitions nested inside the children must not claim the panel's node as well.
+    let claimed = match role {
+        TransitionRole::Child(_) => None,
+        _ => try_use_context::<TransitionContext<G>>(cx)
+            .filter(|context| context.node_ref.try_get_raw().is_none()),
+    };
+    let panel = claimed.and_then(|_| try_use_context::<PanelTransitionContext>(cx));
+    let state = match panel {
+        Some(panel) => panel.state,
+        None => create_signal(cx, TransitionState::Hidden),
+    };
+    // A panel kept mounted stays mounted through its transition as well.
+    let unmount = parts.unmount && panel.map_or(true, |panel| panel.unmount);
+    state.set(if parts.show.get() {
+        TransitionState::Entered
+    } else {
+        TransitionState::Hidden
+    });
+
+    let node = get_ref(cx, &parts.attributes);
+
+    let id = create_id();
+    #[cfg(target_arch = "wasm32")]
+    let selector = create_ref(cx, format!("[data-sh-transition-id=\"{id}\"]"));
+    #[cfg(target_arch = "wasm32")]
+    let phase = create_ref(cx, RefCell::new(Rc::new(Cell::new(true))));
+    #[cfg(target_arch = "wasm32")]
+    let current_animation = create_ref(cx, RefCell::new(None::<Animation>));
+    #[cfg(target_arch = "wasm32")]
+    let motion = use_motion(cx, properties.reduced_motion);
+
+    #[cfg(target_arch = "wasm32")]
+    let start_phase = move || {
+        if let Some(animation) = current_animation.borrow_mut().take() {
+            animation.cancel();
+        }
+        next_phase(phase)
+    };
+    #[cfg(target_arch = "wasm32")]
+    on_cleanup(cx, move || {
+        start_phase();
+    });
+
+    #[cfg(target_arch = "wasm32")]
+    let enter = move |element: &DomNode, window: Window, appear: bool| {
+        let Properties {
+            entered,
+            before_enter,
+            after_enter,
+            ..
+        } = properties;
+        let (enter, enter_from, enter_to) = properties.enter_classes(appear);
+        let motion = *motion.get_untracked();
+
+        let done = start_phase();
+        left.set(false);
+        state.set(TransitionState::Entering);
+
+        let end_transition = {
+            move || {
+                state.set(TransitionState::Entered);
+                if let Some(element) = web_sys::window()
+                    .and_then(|window| window.document())
+                    .and_then(|document| document.query_selector(selector).unwrap())
+                {
+                    if motion == Motion::Shortened {
+                        set_shortened(&element, false);
+                    }
+                    remove_class(&element, enter);
+                    remove_class(&element, enter_to);
+                    add_class(&element, entered);
+                    if let Some(after_enter) = after_enter.as_ref() {
+                        after_enter();
+                    }
+                }
+            }
+        };
+
+        if let Some(before_enter) = before_enter.as_ref() {
+            before_enter();
+        }
+
+        if motion == Motion::Skipped {
+            done.set(true);
+            end_transition();
+            return;
+        }
+
+        if let Some(animation) = properties.animation.as_ref() {
+            let element: Element = element.to_web_sys().unchecked_into();
+            *current_animation.borrow_mut() = Some(run_animation(
+                &element,
+                &shortened(animation, motion),
+                true,
+                done,
+                end_transition,
+            ));
+            return;
+        }
+
+        if enter.is_empty() && enter_from.is_empty() && enter_to.is_empty() {
+            done.set(true);
+            end_transition();
+            return;
+        }
+
+        if motion == Motion::Shortened {
+            set_shortened(&element.to_web_sys(), true);
+        }
+        element.add_class(enter);
+        element.add_class(enter_from);
+
+        next_frame(&window, {
+            let element = element.clone();
+            move || {
+                element.remove_class(enter_from);
+                element.add_class(enter_to);
+            }
+        });
+
+        on_transition_end(element, done, end_transition);
+    };
+
+    #[cfg(target_arch = "wasm32")]
+    let leave = move |element: &DomNode, window: Window| {
+        let Properties {
+            leave,
+            leave_from,
+            leave_to,
+            entered,
+            before_leave,
+            after_leave,
+            ..
+        } = properties;
+
+        let motion = *motion.get_untracked();
+
+        let done = start_phase();
+        state.set(TransitionState::Leaving);
+
+        if let Some(before_leave) = before_leave {
+            before_leave();
+        }
+
+        let end_transition = move || {
+            if let Some(element) = web_sys::window()
+                .and_then(|window| window.document())
+                .and_then(|document| document.query_selector(selector).unwrap())
+            {
+                if motion == Motion::Shortened {
+                    set_shortened(&element, false);
+                }
+                remove_class(&element, leave);
+                remove_class(&element, leave_to);
+                if let TransitionRole::Root(_) = role {
+                    // The root stays mounted until every child has finished leaving.
+                    left.set(true);
+                } else {
+                    visible.set(false);
+                    state.set(TransitionState::Hidden);
+                    if let Some(after_leave) = after_leave {
+                        after_leave();
+                    }
+                }
+            }
+        };
+
+        element.remove_class(entered);
+
+        if motion == Motion::Skipped {
+            done.set(true);
+            end_transition();
+            return;
+        }
+
+        if let Some(animation) = properties.animation.as_ref() {
+            let element: Element = element.to_web_sys().unchecked_into();
+            *current_animation.borrow_mut() = Some(run_animation(
+                &element,
+                &shortened(animation, motion),
+                false,
+                done,
+                end_transition,
+            ));
+            return;
+        }
+
+        if !properties.has_leave() {
+            done.set(true);
+            end_transition();
+            return;
+        }
+
+        if motion == Motion::Shortened {
+            set_shortened(&element.to_web_sys(), true);
+        }
+        element.add_class(leave);
+        element.add_class(leave_from);
+
+        let closure = Rc::new(RefCell::new(None));
+        let c = closure.clone();
+
+        let _ = window.request_animation_frame({
+            let leave_from = leave_from.clone();
+            let leave_to = leave_to.clone();
+            let element = element.clone();
+
+            *closure.borrow_mut() = Some(Closure::<dyn Fn()>::new(move || {
+                element.remove_class(&leave_from);
+                element.add_class(&leave_to);
+                let _ = c.borrow_mut().take();
+            }));
+            closure.borrow().as_ref().unwrap().as_ref().unchecked_ref()
+        });
+
+        on_transition_end(element, done, end_transition);
+    };
+
+    #[cfg(target_arch = "wasm32")]
+    let mut initial = true;
+
+    create_effect(cx, move || {
+        let show = parts.show.clone();
+        let should_show = *create_selector(cx, move || show.get()).get();
+
+        if should_show {
+            visible.set(true);
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            if !initial {
+                if let Some((window, node)) = web_sys::window().zip(dom_node(node)) {
+                    if should_show {
+                        enter(&node, window, false);
+                    } else {
+                        leave(&node, window);
+                    }
+                }
+            } else {
+                initial = false;
+            }
+        }
+    });
+
+    match role {
+        TransitionRole::Root(root) => {
+            create_effect(cx, move || {
+                if *left.get() && *root.visible_children.get() == 0 {
+                    left.set(false);
+                    visible.set(false);
+                    state.set(TransitionState::Hidden);
+                    if let Some(after_leave) = properties.after_leave.as_ref() {
+                        after_leave();
+                    }
+                }
+            });
+        }
+        TransitionRole::Child(root) => {
+            let registered = create_ref(cx, Cell::new(false));
+            create_effect(cx, move || {
+                let visible = *visible.get();
+                if visible != registered.get() {
+                    if visible {
+                        root.register_child();
+                    } else {
+                        root.unregister_child();
+                    }
+                    registered.set(visible);
+                }
+            });
+            on_cleanup(cx, move || {
+                if registered.get() {
+                    root.unregister_child();
+                }
+            });
+        }
+        TransitionRole::Standalone => {}
+    }
+
+    let view = parts.element.call(cx);
+    let element = view.as_node().unwrap();
+
+    node.set(element.clone());
+
+    // Appearing is deferred to the next frame so the element is attached (or hydrated) first.
+    // The starting classes are applied right away to avoid a flash of the final state.
+    #[cfg(target_arch = "wasm32")]
+    if appear {
+        if let Some((window, node)) = web_sys::window().zip(dom_node(node)) {
+            state.set(TransitionState::Entering);
+            if properties.animation.is_none() && *motion.get_untracked() != Motion::Skipped {
+                let (enter_class, enter_from, _) = properties.enter_classes(true);
+                add_class(&node.to_web_sys(), enter_class);
+                add_class(&node.to_web_sys(), enter_from);
+            }
+            next_frame(&window.clone(), move || enter(&node, window, true));
+        }
+    }
+
+    if let Some(context) = claimed {
+        context.node_ref.set(element.clone());
+    }
+
+    let class = class!(cx, parts);
+    let children = scoped_children(cx, parts.children, |cx| {
+        provide_transition_state(cx, state);
+        if let TransitionRole::Root(root) = role {
+            provide_context(cx, root.clone());
+        }
+    });
+
+    element.set_class_name((*class.get()).clone().into());
+    element.set_children(cx, children);
+    element.apply_attributes(cx, &parts.attributes);
+    element.set_attribute("data-sh-transition-id".into(), id.into());
+    create_effect(cx, {
+        let element = element.clone();
+        move || match *state.get() {
+            TransitionState::Hidden => element.remove_attribute("data-sh-transition".into()),
+            current => element.set_attribute("data-sh-transition".into(), current.as_str().into()),
+        }
+    });
+
+    if unmount {
+        view! { cx,
+            (if *visible.get() {
+                node.set(view.as_node().unwrap().clone());
+                view.clone()
+            } else {
+                View::empty()
+            })
+        }
+    } else {
+        element.set_dyn_hidden(cx, move || !*visible.get());
+        view
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sycamore::prelude::*;
+
+    use super::{use_transition_state, Transition};
+
+    #[test]
+    pub fn test_appear_ssr_has_no_classes() {
+        let view = sycamore::render_to_string(move |cx| {
+            view! { cx,
+                Transition(
+                    show = true,
+                    appear = true,
+                    appear_active = "transition-opacity",
+                    appear_from = "opacity-0",
+                    appear_to = "opacity-100",
+                ) { "test" }
+            }
+        });
+
+        assert!(!view.contains("opacity"));
+        assert!(view.contains("test"));
+    }
+
+    #[test]
+    pub fn test_ssr_transition_state() {
+        #[component]
+        fn State<G: Html>(cx: Scope) -> View<G> {
+            let state = use_transition_state(cx);
+            view! { cx, (state.get().as_str()) }
+        }
+
+        let view = sycamore::render_to_string(move |cx| {
+            view! { cx,
+                Transition(show = true) { State {} }
+            }
+        });
+
+        assert!(view.contains("data-sh-transition=\"entered\""));
+        assert!(view.contains(">entered<"));
+    }
+}
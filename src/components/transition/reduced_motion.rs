@@ -0,0 +1,97 @@
+use sycamore::prelude::*;
+
+use crate::hooks::use_prefers_reduced_motion;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReducedMotionPolicy {
+    #[default]
+    Skip,
+    Shorten,
+    Ignore,
+}
+
+#[derive(Clone, Copy)]
+struct ReducedMotionContext(ReducedMotionPolicy);
+
+/// Sets the policy used by every transition rendered inside `cx` that doesn't set its own
+/// `reduced_motion`.
+pub fn provide_reduced_motion_policy(cx: Scope<'_>, policy: ReducedMotionPolicy) {
+    provide_context(cx, ReducedMotionContext(policy));
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), allow(unused))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum Motion {
+    Full,
+    Shortened,
+    Skipped,
+}
+
+impl Motion {
+    fn new(policy: ReducedMotionPolicy, reduced: bool) -> Self {
+        match (policy, reduced) {
+            (_, false) | (ReducedMotionPolicy::Ignore, _) => Motion::Full,
+            (ReducedMotionPolicy::Shorten, true) => Motion::Shortened,
+            (ReducedMotionPolicy::Skip, true) => Motion::Skipped,
+        }
+    }
+}
+
+// The per-instance policy takes precedence over the provided one.
+fn use_policy(cx: Scope<'_>, policy: Option<ReducedMotionPolicy>) -> ReducedMotionPolicy {
+    policy
+        .or_else(|| try_use_context::<ReducedMotionContext>(cx).map(|context| context.0))
+        .unwrap_or_default()
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), allow(unused))]
+pub(super) fn use_motion(
+    cx: Scope<'_>,
+    policy: Option<ReducedMotionPolicy>,
+) -> &ReadSignal<Motion> {
+    let policy = use_policy(cx, policy);
+    let reduced = use_prefers_reduced_motion(cx);
+
+    create_memo(cx, move || Motion::new(policy, *reduced.get()))
+}
+
+#[cfg(test)]
+mod tests {
+    use sycamore::prelude::*;
+
+    use super::{provide_reduced_motion_policy, use_policy, Motion, ReducedMotionPolicy};
+
+    #[test]
+    pub fn test_motion_follows_policy_only_when_reduced() {
+        for policy in [
+            ReducedMotionPolicy::Skip,
+            ReducedMotionPolicy::Shorten,
+            ReducedMotionPolicy::Ignore,
+        ] {
+            assert_eq!(Motion::new(policy, false), Motion::Full);
+        }
+        assert_eq!(
+            Motion::new(ReducedMotionPolicy::Skip, true),
+            Motion::Skipped
+        );
+        assert_eq!(
+            Motion::new(ReducedMotionPolicy::Shorten, true),
+            Motion::Shortened
+        );
+        assert_eq!(Motion::new(ReducedMotionPolicy::Ignore, true), Motion::Full);
+    }
+
+    #[test]
+    pub fn test_instance_policy_overrides_provided_one() {
+        create_scope_immediate(|cx| {
+            assert_eq!(use_policy(cx, None), ReducedMotionPolicy::Skip);
+
+            provide_reduced_motion_policy(cx, ReducedMotionPolicy::Shorten);
+            assert_eq!(use_policy(cx, None), ReducedMotionPolicy::Shorten);
+            assert_eq!(
+                use_policy(cx, Some(ReducedMotionPolicy::Ignore)),
+                ReducedMotionPolicy::Ignore
+            );
+        });
+    }
+}
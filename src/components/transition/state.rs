@@ -0,0 +1,67 @@
+use sycamore::prelude::*;
+
+use crate::utils::{as_static, scoped_children};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransitionState {
+    Entering,
+    Entered,
+    Leaving,
+    Hidden,
+}
+
+impl TransitionState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransitionState::Entering => "entering",
+            TransitionState::Entered => "entered",
+            TransitionState::Leaving => "leaving",
+            TransitionState::Hidden => "hidden",
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct TransitionStateContext {
+    state: &'static ReadSignal<TransitionState>,
+}
+
+pub(crate) fn provide_transition_state(cx: Scope<'_>, state: &ReadSignal<TransitionState>) {
+    provide_context(
+        cx,
+        TransitionStateContext {
+            state: as_static(state),
+        },
+    );
+}
+
+// Provided by panels next to `TransitionContext`, so the transition rendering the panel reports
+// its state to the panel's children and follows the panel's `unmount`.
+#[derive(Clone, Copy)]
+pub(crate) struct PanelTransitionContext {
+    pub state: &'static Signal<TransitionState>,
+    pub unmount: bool,
+}
+
+// Panels render their children outside of the transition, so the state is provided up front.
+pub(crate) fn transition_children<'cx, G: Html>(
+    cx: Scope<'cx>,
+    children: Children<'cx, G>,
+    transition: bool,
+) -> (View<G>, &'static Signal<TransitionState>) {
+    let state = as_static(create_signal(cx, TransitionState::Hidden));
+    let children = if transition {
+        scoped_children(cx, children, |cx| provide_transition_state(cx, state))
+    } else {
+        children.call(cx)
+    };
+    (children, state)
+}
+
+pub fn use_transition_state(cx: Scope<'_>) -> &ReadSignal<TransitionState> {
+    match try_use_context::<TransitionStateContext>(cx) {
+        Some(context) => context.state,
+        // Without a transition, anything that renders is fully shown.
+        None => create_signal(cx, TransitionState::Entered),
+    }
+}
@@ -11,7 +11,17 @@ pub fn generate_id() -> u32 {
 }
  */
 
-use sycamore::stable_id::create_unique_id;
+#[cfg(target_arch = "wasm32")]
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+
+use sycamore::{prelude::*, stable_id::create_unique_id};
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::{prelude::Closure, JsCast};
+#[cfg(target_arch = "wasm32")]
+use web_sys::MediaQueryList;
 
 pub fn create_id() -> String {
     let id = create_unique_id();
@@ -22,3 +32,65 @@ pub fn create_id() -> String {
         format!("headlessui-sycamore-{}", id.1)
     }
 }
+
+// One `matchMedia` listener is shared by every transition, it is removed with the last of them.
+#[cfg(target_arch = "wasm32")]
+struct ReducedMotionQuery {
+    reduced: RcSignal<bool>,
+    query: MediaQueryList,
+    on_change: Closure<dyn Fn()>,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl ReducedMotionQuery {
+    fn shared() -> Option<Rc<Self>> {
+        thread_local! {
+            static QUERY: RefCell<Weak<ReducedMotionQuery>> = RefCell::new(Weak::new());
+        }
+
+        QUERY.with(|shared| {
+            if let Some(query) = shared.borrow().upgrade() {
+                return Some(query);
+            }
+            let query = web_sys::window()?
+                .match_media("(prefers-reduced-motion: reduce)")
+                .ok()??;
+            let reduced = create_rc_signal(query.matches());
+            let on_change = Closure::<dyn Fn()>::new({
+                let query = query.clone();
+                let reduced = reduced.clone();
+                move || reduced.set(query.matches())
+            });
+            let _ = query
+                .add_event_listener_with_callback("change", on_change.as_ref().unchecked_ref());
+
+            let query = Rc::new(Self {
+                reduced,
+                query,
+                on_change,
+            });
+            *shared.borrow_mut() = Rc::downgrade(&query);
+            Some(query)
+        })
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Drop for ReducedMotionQuery {
+    fn drop(&mut self) {
+        let _ = self
+            .query
+            .remove_event_listener_with_callback("change", self.on_change.as_ref().unchecked_ref());
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), allow(unused))]
+pub fn use_prefers_reduced_motion(cx: Scope<'_>) -> &ReadSignal<bool> {
+    #[cfg(target_arch = "wasm32")]
+    if let Some(query) = ReducedMotionQuery::shared() {
+        let query = create_ref(cx, query);
+        return create_memo(cx, move || *query.reduced.get());
+    }
+
+    create_signal(cx, false)
+}
@@ -116,23 +116,24 @@ pub fn focus_last(nodes: NodeList) -> bool {
     }
 }
 
-pub fn focus_match(nodes: NodeList, character: &str) {
-    let lower = character.to_lowercase();
-    for i in 0..nodes.length() {
-        if let Some(el) = nodes
-            .get(i)
-            .as_ref()
-            .and_then(|node| node.dyn_ref::<HtmlElement>())
-        {
-            if el
-                .text_content()
+/// Finds the first focusable node from `start` on whose text starts with `query`, wrapping around.
+pub fn find_match(nodes: &NodeList, query: &str, start: u32) -> Option<HtmlElement> {
+    let lower = query.to_lowercase();
+    let length = nodes.length();
+    (0..length)
+        .filter_map(|i| nodes.get((start + i) % length))
+        .filter(|node| is_focusable(node.clone()))
+        .filter_map(|node| node.dyn_into::<HtmlElement>().ok())
+        .find(|el| {
+            el.text_content()
                 .map(|text| text.to_lowercase().starts_with(&lower))
                 .unwrap_or(false)
-            {
-                let _ = el.focus();
-                return;
-            }
-        }
+        })
+}
+
+pub fn focus_match(nodes: NodeList, character: &str) {
+    if let Some(el) = find_match(&nodes, character, 0) {
+        let _ = el.focus();
     }
 }
 
@@ -1,9 +1,10 @@
-use std::mem;
+use std::{mem, rc::Rc};
 
 use sycamore::{prelude::*, rt::JsCast};
 use web_sys::{HtmlElement, NodeList};
 
 use super::focus_navigation::*;
+use crate::components::{VirtualList, VirtualListSlot};
 
 fn query_nodes(el: HtmlElement, owner_id: &str) -> NodeList {
     el.query_selector_all(&format!("[data-sh-owner=\"{owner_id}\"]"))
@@ -14,6 +15,7 @@ fn query_nodes(el: HtmlElement, owner_id: &str) -> NodeList {
 pub struct FocusNavigator<'cx, G: Html> {
     pub owner_id: String,
     pub internal_ref: &'cx NodeRef<G>,
+    virtual_list: Option<VirtualListSlot>,
 }
 
 impl<'cx, G: Html> FocusNavigator<'cx, G> {
@@ -22,12 +24,36 @@ impl<'cx, G: Html> FocusNavigator<'cx, G> {
         query_nodes(internal_ref, &self.owner_id)
     }
 
+    // Virtualized options are navigated over the full data set instead of the rendered nodes.
+    fn virtual_list(&self) -> Option<(Rc<VirtualList<'static>>, HtmlElement)> {
+        let list = self.virtual_list.as_ref()?.get()?;
+        Some((list, as_html_element(self.internal_ref)?))
+    }
+
+    fn focus_virtual(&self, list: &VirtualList, root: &HtmlElement, index: Option<usize>) {
+        if let Some(node) = index.and_then(|index| list.reveal(root, &self.owner_id, index)) {
+            let _ = node.focus();
+        }
+    }
+
+    fn step_virtual(&self, node: &NodeRef<G>, forward: bool, continuous: bool) -> bool {
+        let (Some((list, root)), Some(node)) = (self.virtual_list(), as_html_element(node)) else {
+            return false;
+        };
+        let current = list.index_of(&root, &self.owner_id, &node);
+        self.focus_virtual(&list, &root, list.step(current, forward, !continuous));
+        true
+    }
+
     pub fn set_checked(&self, node: &NodeRef<G>) {
         let node: HtmlElement = node.get::<DomNode>().unchecked_into();
         let _ = node.focus();
     }
 
     pub fn set_next_checked(&self, node: &NodeRef<G>, continuous: bool) {
+        if self.step_virtual(node, true, continuous) {
+            return;
+        }
         if let Some(node) = node.get::<DomNode>().as_ref().dyn_ref::<HtmlElement>() {
             if continuous {
                 focus_next_continuous(self.query(), node);
@@ -38,6 +64,9 @@ impl<'cx, G: Html> FocusNavigator<'cx, G> {
     }
 
     pub fn set_prev_checked(&self, node: &NodeRef<G>, continuous: bool) {
+        if self.step_virtual(node, false, continuous) {
+            return;
+        }
         let node = node.get::<DomNode>();
         if let Some(node) = node.as_ref().dyn_ref::<HtmlElement>() {
             if continuous {
@@ -49,14 +78,28 @@ impl<'cx, G: Html> FocusNavigator<'cx, G> {
     }
 
     pub fn set_first_checked(&self) {
+        if let Some((list, root)) = self.virtual_list() {
+            self.focus_virtual(&list, &root, list.step(None, true, false));
+            return;
+        }
         focus_first(self.query());
     }
 
     pub fn set_last_checked(&self) {
+        if let Some((list, root)) = self.virtual_list() {
+            self.focus_virtual(&list, &root, list.step(None, false, false));
+            return;
+        }
         focus_last(self.query());
     }
 
     pub fn set_first_match(&self, character: &str) {
+        if let Some((list, root)) = self.virtual_list() {
+            if let Some(index) = list.find(character, 0) {
+                self.focus_virtual(&list, &root, index);
+                return;
+            }
+        }
         focus_match(self.query(), character);
     }
 }
@@ -66,6 +109,12 @@ impl<G: Html> FocusNavigator<'static, G> {
         Self {
             owner_id,
             internal_ref: unsafe { mem::transmute(internal_ref) },
+            virtual_list: None,
         }
     }
+
+    pub(crate) fn with_virtual_list(mut self, slot: VirtualListSlot) -> Self {
+        self.virtual_list = Some(slot);
+        self
+    }
 }
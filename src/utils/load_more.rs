@@ -0,0 +1,88 @@
+use sycamore::{builder::prelude::li, prelude::*};
+use sycamore_utils::{DynamicElement, ReactiveBool};
+#[cfg(target_arch = "wasm32")]
+use {
+    super::as_static,
+    js_sys::Array,
+    sycamore::rt::JsCast,
+    wasm_bindgen::prelude::Closure,
+    web_sys::{Element, IntersectionObserver, IntersectionObserverEntry},
+};
+
+use super::SetDynAttr;
+
+// Placed after the last option. While it is scrolled into view and more options are
+// available, the next page is requested. Appended options are inserted before it, and it
+// opts out of scroll anchoring so the browser keeps the visible options in place instead.
+pub(crate) fn load_more_sentinel<'cx, G: Html>(
+    cx: Scope<'cx>,
+    name: &'static str,
+    on_load_more: Option<Box<dyn Fn() + 'cx>>,
+    has_more: ReactiveBool<'cx>,
+    loading_more: ReactiveBool<'cx>,
+) -> View<G> {
+    let Some(on_load_more) = on_load_more else {
+        return View::empty();
+    };
+    let on_load_more = create_ref(cx, on_load_more);
+    let has_more = create_ref(cx, has_more);
+    let loading_more = create_ref(cx, loading_more);
+    let visible = create_signal(cx, false);
+
+    // Runs again once a page has loaded, in case the sentinel is still visible afterwards.
+    create_effect(cx, move || {
+        if *visible.get() && has_more.get() && !loading_more.get() {
+            untrack(|| on_load_more());
+        }
+    });
+
+    let element: DynamicElement<G> = li.into();
+    let view = element.call(cx);
+    let element = view.as_node().unwrap();
+
+    element.set_attribute("data-sh".into(), name.into());
+    element.set_attribute("role".into(), "presentation".into());
+    element.set_attribute("aria-hidden".into(), "true".into());
+    element.set_attribute("style".into(), "overflow-anchor: none;".into());
+    element.set_dyn_bool(cx, "data-sh-loading", move || loading_more.get());
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        let node = create_node_ref(cx);
+        node.set(element.clone());
+        if let Some(node) = node.try_get::<DomNode>() {
+            observe(cx, node.to_web_sys().unchecked_into(), visible);
+        }
+    }
+
+    view! { cx,
+        (if has_more.get() {
+            view.clone()
+        } else {
+            View::empty()
+        })
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn observe<'cx>(cx: Scope<'cx>, target: Element, visible: &'cx Signal<bool>) {
+    let visible = as_static(visible);
+    let callback = Closure::<dyn FnMut(Array)>::new(move |entries: Array| {
+        if let Some(entry) = entries
+            .iter()
+            .last()
+            .and_then(|entry| entry.dyn_into::<IntersectionObserverEntry>().ok())
+        {
+            visible.set(entry.is_intersecting());
+        }
+    });
+    let Ok(observer) = IntersectionObserver::new(callback.as_ref().unchecked_ref()) else {
+        return;
+    };
+    observer.observe(&target);
+
+    on_cleanup(cx, move || {
+        observer.disconnect();
+        drop(callback);
+    });
+}
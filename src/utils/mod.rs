@@ -1,10 +1,14 @@
 pub mod focus_navigation;
 pub mod focus_navigator;
 mod focus_start_point;
+mod load_more;
+mod option_text;
 
 use std::{borrow::Cow, mem};
 
 pub use focus_start_point::*;
+pub(crate) use load_more::*;
+pub(crate) use option_text::*;
 use sycamore::{
     prelude::*,
     utils::{apply_attribute, render::insert},
@@ -67,6 +71,10 @@ pub trait SetDynAttr<G: Html> {
         N: Into<Cow<'static, str>> + 'cx,
         F: FnMut() -> bool + 'cx;
 
+    fn set_dyn_hidden<'cx, F>(&self, cx: Scope<'cx>, hidden: F)
+    where
+        F: FnMut() -> bool + 'cx;
+
     fn apply_attributes<'cx>(&self, cx: Scope<'cx>, attrs: &Attributes<'cx, G>);
 
     fn set_children(&self, cx: Scope<'_>, children: View<G>);
@@ -100,6 +108,22 @@ impl<G: Html> SetDynAttr<G> for G {
         });
     }
 
+    fn set_dyn_hidden<'cx, F>(&self, cx: Scope<'cx>, mut hidden: F)
+    where
+        F: FnMut() -> bool + 'cx,
+    {
+        let el = self.clone();
+        create_effect(cx, move || {
+            if hidden() {
+                el.set_attribute("hidden".into(), "".into());
+                el.set_attribute("aria-hidden".into(), "true".into());
+            } else {
+                el.remove_attribute("hidden".into());
+                el.remove_attribute("aria-hidden".into());
+            }
+        });
+    }
+
     fn apply_attributes<'cx>(&self, cx: Scope<'cx>, attrs: &Attributes<'cx, G>) {
         for (name, value) in attrs.drain() {
             apply_attribute(cx, self.clone(), name.clone(), value);
@@ -0,0 +1,62 @@
+use std::mem;
+
+use sycamore::prelude::*;
+
+use crate::hooks::create_id;
+
+/// Ids for the label and description parts of an option, shared with the parts through context.
+#[derive(Clone)]
+pub(crate) struct OptionText {
+    label_id: String,
+    description_id: String,
+    has_label: &'static Signal<bool>,
+    has_description: &'static Signal<bool>,
+}
+
+impl OptionText {
+    pub fn new(cx: Scope<'_>) -> Self {
+        Self {
+            label_id: create_id(),
+            description_id: create_id(),
+            has_label: unsafe { mem::transmute(create_signal(cx, false)) },
+            has_description: unsafe { mem::transmute(create_signal(cx, false)) },
+        }
+    }
+
+    pub fn register_label(&self, cx: Scope<'_>) -> String {
+        Self::register(cx, self.has_label);
+        self.label_id.clone()
+    }
+
+    pub fn register_description(&self, cx: Scope<'_>) -> String {
+        Self::register(cx, self.has_description);
+        self.description_id.clone()
+    }
+
+    fn register(cx: Scope<'_>, present: &'static Signal<bool>) {
+        present.set(true);
+        on_cleanup(cx, move || present.set(false));
+    }
+
+    /// References the parts from the option, only while they are rendered.
+    pub fn apply<G: Html>(&self, cx: Scope<'_>, element: &G) {
+        for (name, id, present) in [
+            ("aria-labelledby", &self.label_id, self.has_label),
+            (
+                "aria-describedby",
+                &self.description_id,
+                self.has_description,
+            ),
+        ] {
+            let element = element.clone();
+            let id = id.clone();
+            create_effect(cx, move || {
+                if *present.get() {
+                    element.set_attribute(name.into(), id.clone().into());
+                } else {
+                    element.remove_attribute(name.into());
+                }
+            });
+        }
+    }
+}
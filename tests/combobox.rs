@@ -1,5 +1,10 @@
+use gloo_timers::future::TimeoutFuture;
 use headlessui_sycamore::components::{
-    Combobox, ComboboxButton, ComboboxInput, ComboboxLabel, ComboboxOption, ComboboxOptions,
+    create_async_options, use_filtered_options, Combobox, ComboboxAutocomplete, ComboboxButton,
+    ComboboxChip, ComboboxChipRemove, ComboboxClear, ComboboxFilter, ComboboxFreeText,
+    ComboboxGroup, ComboboxGroupLabel, ComboboxInput, ComboboxLabel, ComboboxLoading,
+    ComboboxOption, ComboboxOptionDescription, ComboboxOptionLabel, ComboboxOptions,
+    ComboboxSelection, ComboboxSeparator, ItemHeight, VirtualOptions,
 };
 use sycamore::prelude::*;
 use test_utils::{
@@ -7,7 +12,7 @@ use test_utils::{
 };
 use wasm_bindgen::JsCast;
 use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
-use web_sys::HtmlElement;
+use web_sys::{Event, HtmlElement, HtmlInputElement, InputEvent, InputEventInit};
 
 wasm_bindgen_test_configure!(run_in_browser);
 
@@ -146,3 +151,678 @@ pub fn searching_works() {
         assert_text_content!(options.children().item(0).unwrap(), "Hello"); */
     });
 }
+
+#[wasm_bindgen_test]
+pub fn keyboard_navigation_keeps_focus_in_input() {
+    create_scope_immediate(|cx| {
+        let value = create_signal(cx, Some("a"));
+        let options = create_signal(cx, vec!["a", "b", "c"]);
+
+        let view = view! { cx,
+            Combobox(value = value) {
+                ComboboxInput
+                ComboboxOptions::<&str, _> {
+                    Keyed(iterable = options, view = |cx, option| view! { cx,
+                        ComboboxOption(value = option) { (option) }
+                    }, key = |option| option.to_string())
+                }
+            }
+        };
+
+        sycamore::render_to(|_| view, &test_container());
+
+        let input = query_component("combobox-input");
+        input.unchecked_ref::<HtmlElement>().focus().unwrap();
+        assert_eq!(input.get_attribute("role").unwrap(), "combobox");
+
+        send_key(&input, "ArrowDown");
+
+        let options = query_component("combobox-options");
+        let option_items = options.children();
+        let first = option_items.item(0).unwrap();
+        let second = option_items.item(1).unwrap();
+
+        assert_eq!(
+            input.get_attribute("aria-activedescendant").unwrap(),
+            first.id()
+        );
+
+        send_key(&input, "ArrowDown");
+
+        assert_eq!(
+            input.get_attribute("aria-activedescendant").unwrap(),
+            second.id()
+        );
+        assert_eq!(second.get_attribute("data-sh-active").unwrap(), "");
+        assert_eq!(test_utils::document().active_element().unwrap(), input);
+
+        send_key(&input, "Enter");
+
+        assert_eq!(*value.get(), Some("b"));
+        assert_eq!(input.get_attribute("aria-expanded"), None);
+        assert_eq!(input.get_attribute("aria-activedescendant"), None);
+    });
+}
+
+#[wasm_bindgen_test]
+pub fn groups_are_navigated_and_hidden_when_empty() {
+    create_scope_immediate(|cx| {
+        let value = create_signal(cx, None::<&str>);
+        let recent = create_signal(cx, vec!["a"]);
+        let all = create_signal(cx, vec!["b", "c"]);
+
+        let view = view! { cx,
+            Combobox(value = value) {
+                ComboboxInput
+                ComboboxOptions::<&str, _> {
+                    ComboboxGroup {
+                        ComboboxGroupLabel { "Recent" }
+                        Keyed(iterable = recent, view = |cx, option| view! { cx,
+                            ComboboxOption(value = option) { (option) }
+                        }, key = |option| option.to_string())
+                    }
+                    ComboboxSeparator {}
+                    ComboboxGroup {
+                        ComboboxGroupLabel { "All" }
+                        Keyed(iterable = all, view = |cx, option| view! { cx,
+                            ComboboxOption(value = option) { (option) }
+                        }, key = |option| option.to_string())
+                    }
+                }
+            }
+        };
+
+        sycamore::render_to(|_| view, &test_container());
+
+        let input = query_component("combobox-input");
+        input.unchecked_ref::<HtmlElement>().focus().unwrap();
+        send_key(&input, "ArrowDown");
+
+        let options = query_component("combobox-options");
+        let groups = options.query_selector_all("[role=\"group\"]").unwrap();
+        let first_group = groups.get(0).unwrap().unchecked_into::<HtmlElement>();
+        let label = query_component_in(&first_group, "combobox-group-label");
+        assert_eq!(
+            first_group.get_attribute("aria-labelledby").unwrap(),
+            label.id()
+        );
+        assert_eq!(
+            query_component_in(&options, "combobox-separator").get_attribute("role"),
+            Some("separator".to_string())
+        );
+
+        send_key(&input, "ArrowDown");
+        let active = input.get_attribute("aria-activedescendant").unwrap();
+        assert_text_content!(
+            test_utils::document().get_element_by_id(&active).unwrap(),
+            "b"
+        );
+
+        recent.set(Vec::new());
+        assert_eq!(first_group.get_attribute("hidden"), Some("".to_string()));
+        assert_eq!(
+            groups
+                .get(1)
+                .unwrap()
+                .unchecked_into::<HtmlElement>()
+                .get_attribute("hidden"),
+            None
+        );
+    });
+}
+
+#[wasm_bindgen_test]
+pub fn horizontal_navigation_follows_orientation() {
+    create_scope_immediate(|cx| {
+        let value = create_signal(cx, Some("a"));
+        let options = create_signal(cx, vec!["a", "b", "c"]);
+
+        let view = view! { cx,
+            Combobox(value = value, horizontal = true) {
+                ComboboxInput
+                ComboboxOptions::<&str, _> {
+                    Keyed(iterable = options, view = |cx, option| view! { cx,
+                        ComboboxOption(value = option) { (option) }
+                    }, key = |option| option.to_string())
+                }
+            }
+        };
+
+        sycamore::render_to(|_| view, &test_container());
+
+        let input = query_component("combobox-input");
+        input.unchecked_ref::<HtmlElement>().focus().unwrap();
+        send_key(&input, "ArrowDown");
+
+        let option_items = query_component("combobox-options").children();
+        let active = || input.get_attribute("aria-activedescendant").unwrap();
+        assert_eq!(active(), option_items.item(0).unwrap().id());
+
+        send_key(&input, "ArrowRight");
+        assert_eq!(active(), option_items.item(1).unwrap().id());
+
+        send_key(&input, "ArrowDown");
+        assert_eq!(active(), option_items.item(1).unwrap().id());
+
+        send_key(&input, "ArrowLeft");
+        assert_eq!(active(), option_items.item(0).unwrap().id());
+
+        send_key(&input, "End");
+        assert_eq!(active(), option_items.item(2).unwrap().id());
+
+        send_key(&input, "Home");
+        assert_eq!(active(), option_items.item(0).unwrap().id());
+    });
+}
+
+#[wasm_bindgen_test]
+pub fn input_text_follows_selection() {
+    create_scope_immediate(|cx| {
+        let value = create_signal(cx, Some("a"));
+        let options = create_signal(cx, vec!["a", "b", "c"]);
+
+        let display_value: Box<dyn Fn(&&str) -> String> = Box::new(|option| option.to_uppercase());
+
+        let view = view! { cx,
+            Combobox(value = value, display_value = display_value) {
+                ComboboxInput
+                ComboboxOptions::<&str, _> {
+                    Keyed(iterable = options, view = |cx, option| view! { cx,
+                        ComboboxOption(value = option) { (option) }
+                    }, key = |option| option.to_string())
+                }
+            }
+        };
+
+        sycamore::render_to(|_| view, &test_container());
+
+        let input = query_component("combobox-input")
+            .dyn_into::<HtmlInputElement>()
+            .unwrap();
+        assert_eq!(input.value(), "A");
+
+        value.set(Some("c"));
+        assert_eq!(input.value(), "C");
+
+        input.focus().unwrap();
+        send_key(&input, "ArrowDown");
+        input.set_value("xyz");
+        send_key(&input, "Escape");
+
+        assert_eq!(input.get_attribute("aria-expanded"), None);
+        assert_eq!(input.value(), "C");
+    });
+}
+
+#[wasm_bindgen_test]
+pub fn nullable_combobox_can_be_cleared() {
+    create_scope_immediate(|cx| {
+        let value = create_signal(cx, Some("a"));
+        let options = create_signal(cx, vec!["a", "b", "c"]);
+
+        let display_value: Box<dyn Fn(&&str) -> String> = Box::new(|option| option.to_string());
+
+        let view = view! { cx,
+            Combobox(value = value, display_value = display_value, nullable = true) {
+                ComboboxInput
+                ComboboxClear { "Clear" }
+                ComboboxOptions::<&str, _> {
+                    Keyed(iterable = options, view = |cx, option| view! { cx,
+                        ComboboxOption(value = option) { (option) }
+                    }, key = |option| option.to_string())
+                }
+            }
+        };
+
+        sycamore::render_to(|_| view, &test_container());
+
+        let input = query_component("combobox-input")
+            .dyn_into::<HtmlInputElement>()
+            .unwrap();
+        let clear = query_component("combobox-clear");
+        assert_eq!(input.value(), "a");
+
+        clear.unchecked_ref::<HtmlElement>().click();
+        assert_eq!(*value.get(), None);
+        assert_eq!(input.value(), "");
+
+        value.set(Some("b"));
+        input.focus().unwrap();
+        input.set_value("");
+        send_key(&input, "Escape");
+        assert_eq!(*value.get(), None);
+    });
+}
+
+#[wasm_bindgen_test]
+pub fn inline_autocomplete_selects_suffix() {
+    create_scope_immediate(|cx| {
+        let value = create_signal(cx, None::<&str>);
+        let options = create_signal(cx, vec!["apple", "apricot"]);
+
+        let view = view! { cx,
+            Combobox(value = value) {
+                ComboboxInput(autocomplete = ComboboxAutocomplete::Both)
+                ComboboxOptions::<&str, _> {
+                    Keyed(iterable = options, view = |cx, option| view! { cx,
+                        ComboboxOption(value = option) { (option) }
+                    }, key = |option| option.to_string())
+                }
+            }
+        };
+
+        sycamore::render_to(|_| view, &test_container());
+
+        let input = query_component("combobox-input")
+            .dyn_into::<HtmlInputElement>()
+            .unwrap();
+        assert_eq!(input.get_attribute("aria-autocomplete").unwrap(), "both");
+        input.focus().unwrap();
+
+        let mut init = InputEventInit::new();
+        init.input_type("insertText");
+        input.set_value("ap");
+        let event = InputEvent::new_with_event_init_dict("input", &init).unwrap();
+        input.dispatch_event(&event).unwrap();
+
+        assert_eq!(input.value(), "apple");
+        assert_eq!(input.selection_start().unwrap(), Some(2));
+        assert_eq!(input.selection_end().unwrap(), Some(5));
+
+        send_key(&input, "Enter");
+        assert_eq!(*value.get(), Some("apple"));
+    });
+}
+
+#[wasm_bindgen_test]
+pub fn composition_is_not_interrupted() {
+    create_scope_immediate(|cx| {
+        let value = create_signal(cx, None::<&str>);
+        let query = create_signal(cx, String::new());
+        let options = create_signal(cx, vec!["a", "b"]);
+
+        let view = view! { cx,
+            Combobox(value = value, query = query, default_open = true) {
+                ComboboxInput
+                ComboboxOptions::<&str, _> {
+                    Keyed(iterable = options, view = |cx, option| view! { cx,
+                        ComboboxOption(value = option) { (option) }
+                    }, key = |option| option.to_string())
+                }
+            }
+        };
+
+        sycamore::render_to(|_| view, &test_container());
+
+        let input = query_component("combobox-input")
+            .dyn_into::<HtmlInputElement>()
+            .unwrap();
+        input.focus().unwrap();
+
+        let dispatch = |name: &str| {
+            input.dispatch_event(&Event::new(name).unwrap()).unwrap();
+        };
+
+        dispatch("compositionstart");
+        input.set_value("k");
+        dispatch("input");
+        assert_eq!(*query.get(), "");
+
+        send_key(&input, "Enter");
+        assert_eq!(*value.get(), None);
+        assert_eq!(input.get_attribute("aria-expanded").unwrap(), "");
+
+        input.set_value("か");
+        dispatch("compositionend");
+        assert_eq!(*query.get(), "か");
+    });
+}
+
+#[wasm_bindgen_test]
+pub fn selection_chips_can_be_removed() {
+    create_scope_immediate(|cx| {
+        let value = create_signal(cx, vec!["a"]);
+        let options = create_signal(cx, vec!["a", "b", "c"]);
+
+        let label: Box<dyn Fn(&&str) -> String> = Box::new(|option| option.to_string());
+
+        let view = view! { cx,
+            Combobox(value_multiple = value) {
+                ComboboxSelection::<&str, _, _>(
+                    label = label,
+                    view = |cx, option| view! { cx,
+                        ComboboxChip(value = option) {
+                            (option)
+                            ComboboxChipRemove { "x" }
+                        }
+                    },
+                )
+                ComboboxInput
+                ComboboxOptions::<&str, _> {
+                    Keyed(iterable = options, view = |cx, option| view! { cx,
+                        ComboboxOption(value = option) { (option) }
+                    }, key = |option| option.to_string())
+                }
+            }
+        };
+
+        sycamore::render_to(|_| view, &test_container());
+
+        let selection = query_component("combobox-selection");
+        let announcer = query_component_in(&selection, "combobox-announcer");
+        value.modify().push("b");
+        assert_eq!(selection.children().length(), 3);
+        assert_text_content!(announcer, "b added");
+
+        let input = query_component("combobox-input");
+        input.unchecked_ref::<HtmlElement>().focus().unwrap();
+
+        send_key(&input, "Backspace");
+        let last = selection.children().item(1).unwrap();
+        assert_eq!(last.get_attribute("data-sh-active").unwrap(), "");
+        assert_eq!(value.get().len(), 2);
+
+        send_key(&input, "ArrowLeft");
+        let first = selection.children().item(0).unwrap();
+        assert_eq!(first.get_attribute("data-sh-active").unwrap(), "");
+
+        send_key(&input, "Backspace");
+        assert_eq!(*value.get(), vec!["b"]);
+        assert_text_content!(announcer, "a removed");
+
+        let remove = query_component_in(&selection, "combobox-chip-remove");
+        remove.unchecked_ref::<HtmlElement>().click();
+        assert!(value.get().is_empty());
+    });
+}
+
+#[wasm_bindgen_test]
+pub async fn async_options_work() {
+    let _ = create_scope(|cx| {
+        let value = create_signal(cx, None::<&str>);
+        let query = create_signal(cx, String::new());
+        let source = create_async_options(
+            cx,
+            query,
+            |query: String| async move {
+                Ok::<_, String>(
+                    ["apple", "banana"]
+                        .into_iter()
+                        .filter(|option| option.contains(query.as_str()))
+                        .collect::<Vec<_>>(),
+                )
+            },
+            0,
+        );
+
+        let view = view! { cx,
+            Combobox(value = value, query = query, async_options = source, default_open = true) {
+                ComboboxInput
+                ComboboxOptions::<&str, _> {
+                    ComboboxLoading { "Loading" }
+                    Keyed(iterable = source.options, view = |cx, option| view! { cx,
+                        ComboboxOption(value = option) { (option) }
+                    }, key = |option| option.to_string())
+                }
+            }
+        };
+
+        sycamore::render_to(|_| view, &test_container());
+    });
+
+    let options = query_component("combobox-options");
+    assert_eq!(options.get_attribute("aria-busy").unwrap(), "true");
+    assert_eq!(options.children().length(), 1);
+    assert_text_content!(options.children().item(0).unwrap(), "Loading");
+
+    TimeoutFuture::new(10).await;
+
+    assert_eq!(options.get_attribute("aria-busy").unwrap(), "false");
+    assert_eq!(options.children().length(), 2);
+    assert_text_content!(options.children().item(0).unwrap(), "apple");
+}
+
+#[component]
+fn FilteredOptions<G: Html>(cx: Scope) -> View<G> {
+    let all_options = create_signal(cx, vec!["pineapple", "apple pie", "apple", "banana"]);
+    let options = use_filtered_options(cx, all_options, |option| option.to_string());
+
+    view! { cx,
+        Keyed(iterable = options, view = |cx, option| view! { cx,
+            ComboboxOption(value = option) { (option) }
+        }, key = |option| option.to_string())
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn options_are_filtered_by_the_combobox_query() {
+    create_scope_immediate(|cx| {
+        let value = create_signal(cx, None::<&str>);
+
+        let view = view! { cx,
+            Combobox(value = value, filter = ComboboxFilter::Contains, default_open = true) {
+                ComboboxInput
+                ComboboxOptions::<&str, _> {
+                    FilteredOptions {}
+                }
+            }
+        };
+
+        sycamore::render_to(|_| view, &test_container());
+
+        let options = query_component("combobox-options");
+        assert_eq!(options.children().length(), 4);
+
+        let input = query_component("combobox-input")
+            .dyn_into::<HtmlInputElement>()
+            .unwrap();
+        input.set_value("apple");
+        input.dispatch_event(&Event::new("input").unwrap()).unwrap();
+
+        let option_items = options.children();
+        assert_eq!(option_items.length(), 3);
+        assert_text_content!(option_items.item(0).unwrap(), "apple pie");
+        assert_text_content!(option_items.item(1).unwrap(), "apple");
+        assert_text_content!(option_items.item(2).unwrap(), "pineapple");
+    });
+}
+
+fn type_text(input: &HtmlInputElement, text: &str) {
+    input.set_value(text);
+    input.dispatch_event(&Event::new("input").unwrap()).unwrap();
+}
+
+#[wasm_bindgen_test]
+pub fn values_can_be_created_from_the_query() {
+    create_scope_immediate(|cx| {
+        let value = create_signal(cx, None::<String>);
+        let created = create_signal(cx, Vec::<String>::new());
+
+        let on_create: Box<dyn Fn(String) -> Option<String> + '_> = Box::new(move |query| {
+            created.modify().push(query.clone());
+            Some(query)
+        });
+        let create_label: Box<dyn Fn(&str) -> String> = Box::new(|query| format!("Add {query}"));
+
+        let view = view! { cx,
+            Combobox(value = value, on_create = on_create) {
+                ComboboxInput
+                ComboboxOptions::<String, _>(create_label = create_label) {}
+            }
+        };
+
+        sycamore::render_to(|_| view, &test_container());
+
+        let input = query_component("combobox-input")
+            .dyn_into::<HtmlInputElement>()
+            .unwrap();
+        input.focus().unwrap();
+        type_text(&input, "kiwi");
+
+        let create = query_component("combobox-create-option");
+        assert_text_content!(create, "Add kiwi");
+        create.unchecked_ref::<HtmlElement>().click();
+
+        assert_eq!(*value.get(), Some("kiwi".to_string()));
+        assert_eq!(input.get_attribute("aria-expanded"), None);
+
+        // Enter on text matching no option creates it as well.
+        type_text(&input, "lime");
+        send_key(&input, "Enter");
+
+        assert_eq!(*value.get(), Some("lime".to_string()));
+        assert_eq!(*created.get(), vec!["kiwi".to_string(), "lime".to_string()]);
+    });
+}
+
+#[wasm_bindgen_test]
+pub fn enter_keeps_free_text() {
+    create_scope_immediate(|cx| {
+        let value = create_signal(cx, None::<String>);
+        let created = create_signal(cx, false);
+
+        let on_create: Box<dyn Fn(String) -> Option<String> + '_> = Box::new(move |query| {
+            created.set(true);
+            Some(query)
+        });
+
+        let view = view! { cx,
+            Combobox(value = value, on_create = on_create, free_text = ComboboxFreeText::Keep) {
+                ComboboxInput
+                ComboboxOptions::<String, _> {}
+            }
+        };
+
+        sycamore::render_to(|_| view, &test_container());
+
+        let input = query_component("combobox-input")
+            .dyn_into::<HtmlInputElement>()
+            .unwrap();
+        input.focus().unwrap();
+        type_text(&input, "kiwi");
+        assert_eq!(input.get_attribute("aria-expanded").unwrap(), "true");
+
+        send_key(&input, "Enter");
+
+        assert_eq!(input.get_attribute("aria-expanded"), None);
+        assert_eq!(input.value(), "kiwi");
+        assert_eq!(*value.get(), None);
+        assert!(!*created.get());
+    });
+}
+
+#[wasm_bindgen_test]
+pub fn virtualized_options_navigate_full_data_set() {
+    create_scope_immediate(|cx| {
+        let value = create_signal(cx, None::<i32>);
+        let items = create_signal(cx, (0..1000).collect::<Vec<i32>>());
+
+        let view = view! { cx,
+            Combobox(value = value, default_open = true) {
+                ComboboxInput
+                ComboboxOptions::<i32, _>(style = "height: 100px; overflow: auto;") {
+                    VirtualOptions(
+                        items = items,
+                        item_height = ItemHeight::Fixed(20.0),
+                        view = |cx, item| view! { cx,
+                            ComboboxOption(value = item, style = "height: 20px;") { (item) }
+                        },
+                    )
+                }
+            }
+        };
+
+        sycamore::render_to(|_| view, &test_container());
+
+        let options = query_component("combobox-options");
+        let rendered = options.query_selector_all("[role=\"option\"]").unwrap();
+        assert!(rendered.length() < 50);
+
+        let input = query_component("combobox-input");
+        input.unchecked_ref::<HtmlElement>().focus().unwrap();
+        send_key(&input, "End");
+
+        let active = input.get_attribute("aria-activedescendant").unwrap();
+        assert_text_content!(
+            test_utils::document().get_element_by_id(&active).unwrap(),
+            "999"
+        );
+
+        send_key(&input, "Enter");
+        assert_eq!(*value.get(), Some(999));
+    });
+}
+
+#[wasm_bindgen_test]
+pub fn virtualized_options_shrink_while_scrolled_to_the_end() {
+    create_scope_immediate(|cx| {
+        let value = create_signal(cx, None::<i32>);
+        let items = create_signal(cx, (0..1000).collect::<Vec<i32>>());
+
+        let view = view! { cx,
+            Combobox(value = value, default_open = true) {
+                ComboboxInput
+                ComboboxOptions::<i32, _>(style = "height: 100px; overflow: auto;") {
+                    VirtualOptions(
+                        items = items,
+                        item_height = ItemHeight::Fixed(20.0),
+                        view = |cx, item| view! { cx,
+                            ComboboxOption(value = item, style = "height: 20px;") { (item) }
+                        },
+                    )
+                }
+            }
+        };
+
+        sycamore::render_to(|_| view, &test_container());
+
+        let input = query_component("combobox-input");
+        input.unchecked_ref::<HtmlElement>().focus().unwrap();
+        send_key(&input, "End");
+
+        // E.g. filtering down to a few results, the old range lies past the new items.
+        items.set(vec![0, 1, 2]);
+
+        let options = query_component("combobox-options");
+        let rendered = options.query_selector_all("[role=\"option\"]").unwrap();
+        assert_eq!(rendered.length(), 3);
+    });
+}
+
+#[wasm_bindgen_test]
+pub fn option_parts_are_referenced() {
+    create_scope_immediate(|cx| {
+        let value = create_signal(cx, None::<&str>);
+
+        let view = view! { cx,
+            Combobox(value = value, default_open = true) {
+                ComboboxInput
+                ComboboxOptions::<&str, _> {
+                    ComboboxOption(value = "jane") {
+                        ComboboxOptionLabel { "Jane" }
+                        ComboboxOptionDescription { "jane@example.com" }
+                    }
+                    ComboboxOption(value = "plain") { "Plain" }
+                }
+            }
+        };
+
+        sycamore::render_to(|_| view, &test_container());
+
+        let options = query_component("combobox-options");
+        let rendered = options.query_selector_all("[role=\"option\"]").unwrap();
+        let option = rendered.get(0).unwrap().unchecked_into::<HtmlElement>();
+        let label = query_component_in(&option, "combobox-option-label");
+        let description = query_component_in(&option, "combobox-option-description");
+
+        assert_eq!(option.get_attribute("aria-labelledby").unwrap(), label.id());
+        assert_eq!(
+            option.get_attribute("aria-describedby").unwrap(),
+            description.id()
+        );
+
+        let plain = rendered.get(1).unwrap().unchecked_into::<HtmlElement>();
+        assert_eq!(plain.get_attribute("aria-labelledby"), None);
+        assert_eq!(plain.get_attribute("aria-describedby"), None);
+    });
+}
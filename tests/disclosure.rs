@@ -1,4 +1,4 @@
-use headlessui_sycamore::components::{Disclosure, DisclosureButton, DisclosurePanel};
+use headlessui_sycamore::components::{Disclosure, DisclosureButton, DisclosurePanel, Transition};
 use sycamore::prelude::*;
 use sycamore::reactive::create_scope_immediate;
 use test_utils::{assert_text_content, query_component, send_key, test_container};
@@ -109,3 +109,69 @@ pub fn keyboard_works() {
         assert_eq!(container.children().length(), 1);
     });
 }
+
+#[wasm_bindgen_test]
+pub fn keep_mounted_works() {
+    create_scope_immediate(|cx| {
+        let open = create_signal(cx, false);
+
+        let node = view! { cx,
+            Disclosure(open = open) {
+                DisclosureButton { "Disclosure 1" }
+                DisclosurePanel(unmount = false) {
+                    "Disclosure 1 Content"
+                }
+            }
+        };
+
+        sycamore::render_to(|_| node, &test_container());
+
+        let container = query_component("disclosure");
+        let panel = query_component("disclosure-panel");
+
+        assert_eq!(container.children().length(), 2);
+        assert_eq!(panel.get_attribute("hidden").unwrap(), "");
+        assert_eq!(panel.get_attribute("aria-hidden").unwrap(), "true");
+
+        open.set(true);
+
+        assert_eq!(container.children().length(), 2);
+        assert_eq!(panel.get_attribute("hidden"), None);
+        assert_eq!(panel.get_attribute("aria-hidden"), None);
+    });
+}
+
+#[wasm_bindgen_test]
+pub fn keep_mounted_works_with_transition() {
+    create_scope_immediate(|cx| {
+        let open = create_signal(cx, true);
+
+        let node = view! { cx,
+            Disclosure(open = open) {
+                DisclosureButton { "Disclosure 1" }
+                DisclosurePanel(
+                    unmount = false,
+                    transition = Box::new(|cx, show| view! { cx, Transition(show = show) }),
+                ) {
+                    "Disclosure 1 Content"
+                }
+            }
+        };
+
+        sycamore::render_to(|_| node, &test_container());
+
+        let container = query_component("disclosure");
+        let panel = query_component("disclosure-panel");
+        assert_eq!(panel.get_attribute("hidden"), None);
+
+        open.set(false);
+
+        assert_eq!(container.children().length(), 2);
+        assert_eq!(panel.get_attribute("hidden").unwrap(), "");
+        assert_text_content!(panel, "Disclosure 1 Content");
+
+        open.set(true);
+
+        assert_eq!(panel.get_attribute("hidden"), None);
+    });
+}
@@ -1,11 +1,16 @@
+use gloo_timers::future::TimeoutFuture;
 use headlessui_sycamore::components::{
-    Listbox, ListboxButton, ListboxLabel, ListboxOption, ListboxOptions,
+    use_select_validity, ItemHeight, Listbox, ListboxButton, ListboxGroup, ListboxGroupLabel,
+    ListboxLabel, ListboxOption, ListboxOptionDescription, ListboxOptionLabel, ListboxOptions,
+    ListboxSeparator, SelectKey, SelectSignal, VirtualOptions,
 };
 use sycamore::prelude::*;
-use test_utils::{query_component, query_component_in, test_container};
+use test_utils::{
+    assert_text_content, query_component, query_component_in, send_key, test_container,
+};
 use wasm_bindgen::JsCast;
 use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
-use web_sys::HtmlElement;
+use web_sys::{HtmlElement, KeyboardEvent, KeyboardEventInit};
 
 wasm_bindgen_test_configure!(run_in_browser);
 
@@ -82,3 +87,694 @@ pub fn structure_is_correct() {
         }
     });
 }
+
+#[wasm_bindgen_test]
+pub async fn more_options_are_loaded_at_the_end() {
+    let items = create_rc_signal(vec!["a", "b"]);
+    let _ = create_scope({
+        let items = items.clone();
+        move |cx| {
+            let value = create_signal(cx, None::<&str>);
+            let options = create_memo(cx, {
+                let items = items.clone();
+                move || (*items.get()).clone()
+            });
+            let has_more = create_memo(cx, {
+                let items = items.clone();
+                move || items.get().len() < 4
+            });
+            let on_load_more: Box<dyn Fn()> = Box::new(move || {
+                items.modify().extend(["c", "d"]);
+            });
+
+            let view = view! { cx,
+                Listbox(value = value, default_open = true) {
+                    ListboxOptions::<&str, _>(on_load_more = on_load_more, has_more = has_more) {
+                        Keyed(iterable = options, view = |cx, option| view! { cx,
+                            ListboxOption(value = option) { (option) }
+                        }, key = |option| option.to_string())
+                    }
+                }
+            };
+
+            sycamore::render_to(|_| view, &test_container());
+        }
+    });
+
+    let options = query_component("listbox-options");
+    assert_eq!(
+        query_component_in(&options, "listbox-load-more").get_attribute("aria-hidden"),
+        Some("true".to_string())
+    );
+
+    TimeoutFuture::new(50).await;
+
+    assert_eq!(items.get().len(), 4);
+    assert_eq!(options.children().length(), 4);
+    assert_eq!(options.get_attribute("aria-busy").unwrap(), "false");
+}
+
+#[wasm_bindgen_test]
+pub fn virtualized_options_navigate_full_data_set() {
+    create_scope_immediate(|cx| {
+        let value = create_signal(cx, Some(0));
+        let items = create_signal(cx, (0..1000).collect::<Vec<i32>>());
+
+        let view = view! { cx,
+            Listbox(value = value, default_open = true) {
+                ListboxOptions::<i32, _>(style = "height: 100px; overflow: auto;") {
+                    VirtualOptions(
+                        items = items,
+                        item_height = ItemHeight::Fixed(20.0),
+                        view = |cx, item| view! { cx,
+                            ListboxOption(value = item, style = "height: 20px;") { (item) }
+                        },
+                    )
+                }
+            }
+        };
+
+        sycamore::render_to(|_| view, &test_container());
+
+        let options = query_component("listbox-options");
+        let rendered = options.query_selector_all("[role=\"option\"]").unwrap();
+        assert!(rendered.length() < 50);
+
+        let first = rendered.get(0).unwrap();
+        first.unchecked_ref::<HtmlElement>().focus().unwrap();
+        send_key(first.unchecked_ref(), "End");
+
+        let active = test_utils::document().active_element().unwrap();
+        assert_text_content!(active, "999");
+
+        send_key(&active, "Enter");
+        assert_eq!(*value.get(), Some(999));
+    });
+}
+
+fn send_key_with(element: &web_sys::Element, key: &str, shift: bool, ctrl: bool) {
+    let event = KeyboardEvent::new_with_keyboard_event_init_dict(
+        "keydown",
+        KeyboardEventInit::new()
+            .bubbles(true)
+            .cancelable(true)
+            .key(key)
+            .shift_key(shift)
+            .ctrl_key(ctrl),
+    )
+    .unwrap();
+    element.dispatch_event(&event).unwrap();
+}
+
+#[wasm_bindgen_test]
+pub fn range_selection_extends_from_anchor() {
+    create_scope_immediate(|cx| {
+        let value = create_signal(cx, Vec::<&str>::new());
+
+        let view = view! { cx,
+            Listbox(value_multiple = value, default_open = true) {
+                ListboxOptions::<&str, _> {
+                    ListboxOption(value = "a") { "a" }
+                    ListboxOption(value = "b") { "b" }
+                    ListboxOption(value = "c") { "c" }
+                    ListboxOption(value = "d", disabled = true) { "d" }
+                }
+            }
+        };
+
+        sycamore::render_to(|_| view, &test_container());
+
+        let options = query_component("listbox-options");
+        let first = options.children().item(0).unwrap();
+        first.unchecked_ref::<HtmlElement>().focus().unwrap();
+
+        send_key_with(&first, "ArrowDown", true, false);
+        let active = test_utils::document().active_element().unwrap();
+        send_key_with(&active, "ArrowDown", true, false);
+        assert_eq!(*value.get(), vec!["a", "b", "c"]);
+
+        let active = test_utils::document().active_element().unwrap();
+        send_key_with(&active, "ArrowUp", true, false);
+        assert_eq!(*value.get(), vec!["a", "b"]);
+
+        let active = test_utils::document().active_element().unwrap();
+        send_key(&active, " ");
+        assert_eq!(*value.get(), vec!["a"]);
+
+        send_key_with(&active, "a", false, true);
+        assert_eq!(*value.get(), vec!["a", "b", "c"]);
+    });
+}
+
+// Not comparable because of the score, selections identify users by id.
+#[derive(Clone)]
+struct User {
+    id: u32,
+    score: f64,
+}
+
+impl SelectKey for User {
+    type Key = u32;
+
+    fn select_key(&self) -> u32 {
+        self.id
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn selection_is_compared_by_key() {
+    create_scope_immediate(|cx| {
+        let value = create_signal(cx, Some(User { id: 2, score: 0.5 }));
+
+        let view = view! { cx,
+            Listbox(value = value, default_open = true) {
+                ListboxOptions::<User, _> {
+                    ListboxOption(value = User { id: 1, score: 1.0 }) { "1" }
+                    ListboxOption(value = User { id: 2, score: 1.0 }) { "2" }
+                }
+            }
+        };
+
+        sycamore::render_to(|_| view, &test_container());
+
+        let options = query_component("listbox-options");
+        let first = options.children().item(0).unwrap();
+        let second = options.children().item(1).unwrap();
+        assert_eq!(first.get_attribute("aria-selected"), None);
+        assert_eq!(second.get_attribute("aria-selected").unwrap(), "");
+
+        first.unchecked_ref::<HtmlElement>().click();
+        assert_eq!(
+            value
+                .get()
+                .as_ref()
+                .as_ref()
+                .map(|user| (user.id, user.score)),
+            Some((1, 1.0))
+        );
+    });
+}
+
+#[wasm_bindgen_test]
+pub fn by_overrides_the_selection_key() {
+    create_scope_immediate(|cx| {
+        let value = create_signal(cx, vec![(2, "old name")]);
+
+        let view = view! { cx,
+            Listbox(value_multiple = value, by = |user: &(u32, &str)| user.0, default_open = true) {
+                ListboxOptions::<(u32, &str), _> {
+                    ListboxOption(value = (1, "Jane")) { "Jane" }
+                    ListboxOption(value = (2, "John")) { "John" }
+                }
+            }
+        };
+
+        sycamore::render_to(|_| view, &test_container());
+
+        let options = query_component("listbox-options");
+        let first = options.children().item(0).unwrap();
+        let second = options.children().item(1).unwrap();
+        assert_eq!(first.get_attribute("aria-selected"), None);
+        assert_eq!(second.get_attribute("aria-selected").unwrap(), "");
+
+        // The renamed user is still the same selection.
+        second.unchecked_ref::<HtmlElement>().click();
+        assert!(value.get().is_empty());
+    });
+}
+
+// Neither comparable nor a `SelectKey`, only `by` identifies readings.
+#[derive(Clone)]
+struct Reading {
+    sensor: u32,
+    value: f64,
+}
+
+#[wasm_bindgen_test]
+pub fn values_without_select_key_are_keyed_by() {
+    create_scope_immediate(|cx| {
+        let value = create_signal(
+            cx,
+            Some(Reading {
+                sensor: 2,
+                value: 0.5,
+            }),
+        );
+
+        let view = view! { cx,
+            Listbox(
+                value = SelectSignal::new(value),
+                by = |reading: &Reading| reading.sensor,
+                default_open = true,
+            ) {
+                ListboxOptions::<Reading, _> {
+                    ListboxOption(value = Reading { sensor: 1, value: 1.0 }) { "1" }
+                    ListboxOption(value = Reading { sensor: 2, value: 1.0 }) { "2" }
+                }
+            }
+        };
+
+        sycamore::render_to(|_| view, &test_container());
+
+        let options = query_component("listbox-options");
+        let first = options.children().item(0).unwrap();
+        let second = options.children().item(1).unwrap();
+        assert_eq!(first.get_attribute("aria-selected"), None);
+        assert_eq!(second.get_attribute("aria-selected").unwrap(), "");
+
+        first.unchecked_ref::<HtmlElement>().click();
+        assert_eq!(
+            value
+                .get()
+                .as_ref()
+                .as_ref()
+                .map(|reading| (reading.sensor, reading.value)),
+            Some((1, 1.0))
+        );
+    });
+}
+
+#[wasm_bindgen_test]
+pub fn values_without_select_key_need_by() {
+    create_scope_immediate(|cx| {
+        let value = create_signal(cx, None::<Reading>);
+
+        let view = view! { cx,
+            div(data-testid = "root") {
+                Listbox(value = SelectSignal::new(value)) {
+                    ListboxOptions::<Reading, _> {}
+                }
+            }
+        };
+
+        sycamore::render_to(|_| view, &test_container());
+
+        let root = test_utils::document()
+            .query_selector("[data-testid=\"root\"]")
+            .unwrap()
+            .unwrap();
+        assert_text_content!(root, "Must provide 'by' for values without a 'SelectKey'.");
+    });
+}
+
+#[wasm_bindgen_test]
+pub fn multiple_selection_keeps_pick_order() {
+    create_scope_immediate(|cx| {
+        let value = create_signal(cx, Vec::<&str>::new());
+
+        let view = view! { cx,
+            Listbox(value_multiple = value, default_open = true) {
+                ListboxOptions::<&str, _> {
+                    ListboxOption(value = "a") { "a" }
+                    ListboxOption(value = "b") { "b" }
+                    ListboxOption(value = "c") { "c" }
+                }
+            }
+        };
+
+        sycamore::render_to(|_| view, &test_container());
+
+        let options = query_component("listbox-options");
+        let option = |index| options.children().item(index).unwrap();
+        option(2).unchecked_ref::<HtmlElement>().click();
+        option(0).unchecked_ref::<HtmlElement>().click();
+        assert_eq!(*value.get(), vec!["c", "a"]);
+
+        send_key_with(&option(0), "a", false, true);
+        assert_eq!(*value.get(), vec!["c", "a", "b"]);
+    });
+}
+
+#[wasm_bindgen_test]
+pub fn select_all_keeps_selected_values_up_to_max() {
+    create_scope_immediate(|cx| {
+        let value = create_signal(cx, vec!["c", "x"]);
+
+        let view = view! { cx,
+            Listbox(value_multiple = value, max_selected = 3, default_open = true) {
+                ListboxOptions::<&str, _> {
+                    ListboxOption(value = "a") { "a" }
+                    ListboxOption(value = "b") { "b" }
+                    ListboxOption(value = "c") { "c" }
+                    ListboxOption(value = "d") { "d" }
+                }
+            }
+        };
+
+        sycamore::render_to(|_| view, &test_container());
+
+        // Values missing from the options are dropped, the selected ones stay in front.
+        let options = query_component("listbox-options");
+        send_key_with(&options.children().item(0).unwrap(), "a", false, true);
+        assert_eq!(*value.get(), vec!["c", "a", "b"]);
+    });
+}
+
+#[wasm_bindgen_test]
+pub fn max_selected_disables_remaining_options() {
+    create_scope_immediate(|cx| {
+        let value = create_signal(cx, Vec::<&str>::new());
+        let valid = create_signal(cx, true);
+
+        let view = view! { cx,
+            Listbox(
+                value_multiple = value,
+                min_selected = 1,
+                max_selected = 2,
+                valid = valid,
+                default_open = true,
+            ) {
+                ListboxOptions::<&str, _> {
+                    ListboxOption(value = "a") { "a" }
+                    ListboxOption(value = "b") { "b" }
+                    ListboxOption(value = "c") { "c" }
+                }
+            }
+        };
+
+        sycamore::render_to(|_| view, &test_container());
+
+        assert!(!*valid.get());
+        let container = query_component("listbox");
+        assert_eq!(container.get_attribute("data-sh-invalid").unwrap(), "");
+
+        let options = query_component("listbox-options");
+        let option = |index| options.children().item(index).unwrap();
+        option(0).unchecked_ref::<HtmlElement>().click();
+        option(1).unchecked_ref::<HtmlElement>().click();
+        assert!(*valid.get());
+        assert_eq!(container.get_attribute("data-sh-invalid"), None);
+        assert_eq!(option(2).get_attribute("data-sh-disabled").unwrap(), "true");
+
+        option(2).unchecked_ref::<HtmlElement>().click();
+        assert_eq!(*value.get(), vec!["a", "b"]);
+
+        send_key_with(&option(0), " ", false, false);
+        assert_eq!(
+            option(2).get_attribute("data-sh-disabled").unwrap(),
+            "false"
+        );
+    });
+}
+
+#[component]
+fn Validity<G: Html>(cx: Scope) -> View<G> {
+    let valid = use_select_validity(cx);
+
+    view! { cx,
+        p(data-testid = "validity") { (if *valid.get() { "valid" } else { "invalid" }) }
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn validity_is_provided_to_children() {
+    create_scope_immediate(|cx| {
+        let value = create_signal(cx, Option::<&str>::None);
+
+        let view = view! { cx,
+            Listbox(value = value, required = true, default_open = true) {
+                Validity {}
+                ListboxOptions::<&str, _> {
+                    ListboxOption(value = "a") { "a" }
+                }
+            }
+        };
+
+        sycamore::render_to(|_| view, &test_container());
+
+        let validity = test_utils::document()
+            .query_selector("[data-testid=\"validity\"]")
+            .unwrap()
+            .unwrap();
+        assert_text_content!(validity, "invalid");
+
+        value.set(Some("a"));
+        assert_text_content!(validity, "valid");
+    });
+}
+
+#[wasm_bindgen_test]
+pub fn typing_on_closed_button_selects_in_place() {
+    create_scope_immediate(|cx| {
+        let value = create_signal(cx, Some("apple"));
+
+        let view = view! { cx,
+            Listbox(value = value) {
+                ListboxButton { "Open" }
+                ListboxOptions::<&str, _> {
+                    ListboxOption(value = "apple") { "Apple" }
+                    ListboxOption(value = "banana") { "Banana" }
+                    ListboxOption(value = "blueberry") { "Blueberry" }
+                    ListboxOption(value = "cherry") { "Cherry" }
+                }
+            }
+        };
+
+        sycamore::render_to(|_| view, &test_container());
+
+        let button = query_component("listbox-button");
+        button.unchecked_ref::<HtmlElement>().focus().unwrap();
+
+        send_key(&button, "b");
+        assert_eq!(*value.get(), Some("banana"));
+        assert_eq!(button.get_attribute("aria-expanded"), None);
+
+        send_key(&button, "b");
+        assert_eq!(*value.get(), Some("blueberry"));
+
+        send_key(&button, "b");
+        assert_eq!(*value.get(), Some("banana"));
+        assert_eq!(test_utils::document().active_element().unwrap(), button);
+    });
+}
+
+#[wasm_bindgen_test]
+pub fn typing_on_closed_button_never_deselects() {
+    create_scope_immediate(|cx| {
+        let value = create_signal(cx, Some("apple"));
+
+        let view = view! { cx,
+            Listbox(value = value, toggleable = true) {
+                ListboxButton { "Open" }
+                ListboxOptions::<&str, _> {
+                    ListboxOption(value = "apple") { "Apple" }
+                    ListboxOption(value = "banana") { "Banana" }
+                }
+            }
+        };
+
+        sycamore::render_to(|_| view, &test_container());
+
+        // The only match is the selected option itself.
+        let button = query_component("listbox-button");
+        send_key(&button, "a");
+        assert_eq!(*value.get(), Some("apple"));
+    });
+}
+
+#[wasm_bindgen_test]
+pub fn typing_on_closed_button_searches_virtualized_items() {
+    create_scope_immediate(|cx| {
+        let value = create_signal(cx, Some(0));
+        let items = create_signal(cx, (0..1000).collect::<Vec<i32>>());
+        let text: Box<dyn Fn(&i32) -> String> = Box::new(item_label);
+
+        let view = view! { cx,
+            Listbox(value = value) {
+                ListboxButton { "Open" }
+                ListboxOptions::<i32, _>(unmount = false) {
+                    VirtualOptions(
+                        items = items,
+                        item_height = ItemHeight::Fixed(20.0),
+                        text = text,
+                        view = |cx, item| view! { cx,
+                            ListboxOption(value = item) { (item_label(&item)) }
+                        },
+                    )
+                }
+            }
+        };
+
+        sycamore::render_to(|_| view, &test_container());
+
+        // "Zebra" is never rendered while the list is closed.
+        let button = query_component("listbox-button");
+        send_key(&button, "z");
+        assert_eq!(*value.get(), Some(900));
+    });
+}
+
+#[wasm_bindgen_test]
+pub fn groups_are_navigated_and_hidden_when_empty() {
+    create_scope_immediate(|cx| {
+        let value = create_signal(cx, None::<&str>);
+        let recent = create_signal(cx, vec!["a"]);
+        let all = create_signal(cx, vec!["b", "c"]);
+
+        let view = view! { cx,
+            Listbox(value = value, default_open = true) {
+                ListboxOptions::<&str, _> {
+                    ListboxGroup {
+                        ListboxGroupLabel { "Recent" }
+                        Keyed(iterable = recent, view = |cx, option| view! { cx,
+                            ListboxOption(value = option) { (option) }
+                        }, key = |option| option.to_string())
+                    }
+                    ListboxSeparator {}
+                    ListboxGroup {
+                        ListboxGroupLabel { "All" }
+                        Keyed(iterable = all, view = |cx, option| view! { cx,
+                            ListboxOption(value = option) { (option) }
+                        }, key = |option| option.to_string())
+                    }
+                }
+            }
+        };
+
+        sycamore::render_to(|_| view, &test_container());
+
+        let options = query_component("listbox-options");
+        let groups = options.query_selector_all("[role=\"group\"]").unwrap();
+        let first_group = groups.get(0).unwrap().unchecked_into::<HtmlElement>();
+        let label = query_component_in(&first_group, "listbox-group-label");
+        assert_eq!(label.get_attribute("role").unwrap(), "presentation");
+        assert_eq!(
+            first_group.get_attribute("aria-labelledby").unwrap(),
+            label.id()
+        );
+
+        let separator = query_component_in(&options, "listbox-separator");
+        assert_eq!(separator.get_attribute("role").unwrap(), "separator");
+        assert_eq!(
+            separator.get_attribute("aria-orientation").unwrap(),
+            "horizontal"
+        );
+
+        // Navigation runs from the last option of a group into the next one.
+        let first = query_component_in(&first_group, "listbox-option");
+        first.unchecked_ref::<HtmlElement>().focus().unwrap();
+        send_key(&first, "ArrowDown");
+        let active = test_utils::document().active_element().unwrap();
+        assert_text_content!(active, "b");
+
+        recent.set(Vec::new());
+        assert_eq!(first_group.get_attribute("hidden"), Some("".to_string()));
+        let second_group = groups.get(1).unwrap().unchecked_into::<HtmlElement>();
+        assert_eq!(second_group.get_attribute("hidden"), None);
+    });
+}
+
+#[wasm_bindgen_test]
+pub async fn measured_item_heights_replace_the_estimate() {
+    let _ = create_scope(|cx| {
+        let value = create_signal(cx, None::<i32>);
+        let items = create_signal(cx, (0..100).collect::<Vec<i32>>());
+
+        let view = view! { cx,
+            Listbox(value = value, default_open = true) {
+                ListboxOptions::<i32, _>(style = "height: 100px; overflow: auto;") {
+                    VirtualOptions(
+                        items = items,
+                        item_height = ItemHeight::Measured(10.0),
+                        view = |cx, item| view! { cx,
+                            ListboxOption(value = item, style = "height: 30px;") { (item) }
+                        },
+                    )
+                }
+            }
+        };
+
+        sycamore::render_to(|_| view, &test_container());
+    });
+
+    TimeoutFuture::new(50).await;
+
+    // Four measured options fill the list, plus the overscan. The estimate alone renders 15.
+    let options = query_component("listbox-options");
+    let rendered = options.query_selector_all("[role=\"option\"]").unwrap();
+    assert_eq!(rendered.length(), 4 + 5);
+}
+
+fn item_label(item: &i32) -> String {
+    if *item == 900 {
+        "Zebra".to_string()
+    } else {
+        format!("Item {item}")
+    }
+}
+
+#[wasm_bindgen_test]
+pub async fn typeahead_searches_the_full_data_set() {
+    let _ = create_scope(|cx| {
+        let value = create_signal(cx, Some(0));
+        let items = create_signal(cx, (0..1000).collect::<Vec<i32>>());
+        let text: Box<dyn Fn(&i32) -> String> = Box::new(item_label);
+
+        let view = view! { cx,
+            Listbox(value = value, default_open = true) {
+                ListboxOptions::<i32, _>(style = "height: 100px; overflow: auto;") {
+                    VirtualOptions(
+                        items = items,
+                        item_height = ItemHeight::Fixed(20.0),
+                        text = text,
+                        view = |cx, item| view! { cx,
+                            ListboxOption(value = item, style = "height: 20px;") {
+                                (item_label(&item))
+                            }
+                        },
+                    )
+                }
+            }
+        };
+
+        sycamore::render_to(|_| view, &test_container());
+
+        let first = query_component("listbox-option");
+        first.unchecked_ref::<HtmlElement>().focus().unwrap();
+        send_key(&first, "z");
+        send_key(&first, "e");
+    });
+
+    TimeoutFuture::new(150).await;
+
+    // "Zebra" was far outside the rendered window.
+    let active = test_utils::document().active_element().unwrap();
+    assert_text_content!(active, "Zebra");
+}
+
+#[wasm_bindgen_test]
+pub fn option_parts_are_referenced() {
+    create_scope_immediate(|cx| {
+        let value = create_signal(cx, None::<&str>);
+
+        let view = view! { cx,
+            Listbox(value = value, default_open = true) {
+                ListboxOptions::<&str, _> {
+                    ListboxOption(value = "jane") {
+                        ListboxOptionLabel { "Jane" }
+                        ListboxOptionDescription { "jane@example.com" }
+                    }
+                    ListboxOption(value = "plain") { "Plain" }
+                }
+            }
+        };
+
+        sycamore::render_to(|_| view, &test_container());
+
+        let options = query_component("listbox-options");
+        let rendered = options.query_selector_all("[role=\"option\"]").unwrap();
+        let option = rendered.get(0).unwrap().unchecked_into::<HtmlElement>();
+        let label = query_component_in(&option, "listbox-option-label");
+        let description = query_component_in(&option, "listbox-option-description");
+
+        assert_eq!(option.get_attribute("aria-labelledby").unwrap(), label.id());
+        assert_eq!(
+            option.get_attribute("aria-describedby").unwrap(),
+            description.id()
+        );
+
+        let plain = rendered.get(1).unwrap().unchecked_into::<HtmlElement>();
+        assert_eq!(plain.get_attribute("aria-labelledby"), None);
+        assert_eq!(plain.get_attribute("aria-describedby"), None);
+    });
+}
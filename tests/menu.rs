@@ -1,6 +1,8 @@
-use headlessui_sycamore::components::{Menu, MenuItem};
+use headlessui_sycamore::components::{Menu, MenuItem, MenuItemDescription, MenuItemLabel};
 use sycamore::prelude::*;
-use test_utils::{assert_text_content, document, query_component, send_key, test_container};
+use test_utils::{
+    assert_text_content, document, query_component, query_component_in, send_key, test_container,
+};
 use wasm_bindgen::JsCast;
 use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
 use web_sys::HtmlElement;
@@ -90,3 +92,35 @@ pub fn keyboard_works() {
         // Can't automatically test selection by starting letter because it has a 100ms delay
     });
 }
+
+#[wasm_bindgen_test]
+pub fn item_parts_are_referenced() {
+    create_scope_immediate(|cx| {
+        let node = view! { cx,
+            Menu {
+                MenuItem {
+                    MenuItemLabel { "Jane" }
+                    MenuItemDescription { "jane@example.com" }
+                }
+                MenuItem { "Item 2" }
+            }
+        };
+
+        sycamore::render_to(|_| node, &test_container());
+
+        let container = query_component("menu");
+        let item = container.children().item(0).unwrap();
+        let label = query_component_in(&item, "menu-item-label");
+        let description = query_component_in(&item, "menu-item-description");
+
+        assert_eq!(item.get_attribute("aria-labelledby").unwrap(), label.id());
+        assert_eq!(
+            item.get_attribute("aria-describedby").unwrap(),
+            description.id()
+        );
+
+        let plain = container.children().item(1).unwrap();
+        assert_eq!(plain.get_attribute("aria-labelledby"), None);
+        assert_eq!(plain.get_attribute("aria-describedby"), None);
+    });
+}
@@ -0,0 +1,223 @@
+use std::{cell::Cell, rc::Rc};
+
+use gloo_timers::future::TimeoutFuture;
+use headlessui_sycamore::components::{
+    Keyframe, Transition, TransitionAnimation, TransitionChild, TransitionGroup, TransitionRoot,
+};
+use js_sys::{Array, Function, Reflect};
+use sycamore::prelude::*;
+use test_utils::{document, test_container};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+use web_sys::{Animation, Element, TransitionEvent};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn find(selector: &str) -> Option<Element> {
+    document().query_selector(selector).unwrap()
+}
+
+fn end_transition(element: &Element) {
+    let event = TransitionEvent::new("transitionend").unwrap();
+    element.dispatch_event(&event).unwrap();
+}
+
+#[wasm_bindgen_test]
+pub fn root_waits_for_children_to_leave() {
+    create_scope_immediate(|cx| {
+        let show = create_signal(cx, true);
+
+        let view = view! { cx,
+            TransitionRoot(show = show, class = "root") {
+                TransitionChild(class = "child", leave = "fade") { "Child" }
+            }
+        };
+
+        sycamore::render_to(|_| view, &test_container());
+
+        let child = find(".child").unwrap();
+
+        show.set(false);
+
+        assert!(find(".root").is_some());
+        assert!(child.class_list().contains("fade"));
+
+        end_transition(&child);
+
+        assert!(find(".child").is_none());
+        assert!(find(".root").is_none());
+
+        show.set(true);
+
+        assert!(find(".root").is_some());
+        assert!(find(".child").is_some());
+    });
+}
+
+#[wasm_bindgen_test]
+pub fn appear_runs_on_hydrated_markup() {
+    fn app<G: Html>(cx: Scope) -> View<G> {
+        view! { cx,
+            Transition(
+                show = true,
+                appear = true,
+                appear_active = "appearing",
+                appear_from = "hidden",
+                appear_to = "shown",
+                class = "appear",
+            ) { "Content" }
+        }
+    }
+
+    let container = test_container();
+    container.set_inner_html(&sycamore::render_to_string(app));
+    assert!(!find(".appear").unwrap().class_list().contains("hidden"));
+
+    sycamore::hydrate_to(app, &container);
+
+    let element = find(".appear").unwrap();
+    assert!(element.class_list().contains("appearing"));
+    assert!(element.class_list().contains("hidden"));
+    assert_eq!(
+        element.get_attribute("data-sh-transition").unwrap(),
+        "entering"
+    );
+}
+
+fn height(keyframe: &Keyframe) -> Option<&str> {
+    keyframe
+        .iter()
+        .find(|(property, _)| property == "height")
+        .map(|(_, value)| value.as_ref())
+}
+
+#[wasm_bindgen_test]
+pub fn measured_keyframes_use_the_element_height() {
+    let container = test_container();
+    container.set_inner_html(r#"<div class="measured"><div style="height: 40px"></div></div>"#);
+    let element = find(".measured").unwrap();
+
+    let animation = TransitionAnimation::collapse_height();
+
+    let enter = animation.enter.resolve(&element);
+    assert_eq!(height(&enter[0]), Some("0px"));
+    assert_eq!(height(&enter[1]), Some("40px"));
+
+    // Heights are measured again for every phase.
+    element.set_inner_html(r#"<div style="height: 80px"></div>"#);
+
+    let leave = animation.leave.resolve(&element);
+    assert_eq!(height(&leave[0]), Some("80px"));
+    assert_eq!(height(&leave[1]), Some("0px"));
+}
+
+fn cancel_animations(element: &Element) {
+    let get_animations = Reflect::get(element, &"getAnimations".into()).unwrap();
+    let animations = get_animations
+        .unchecked_ref::<Function>()
+        .call0(element)
+        .unwrap()
+        .unchecked_into::<Array>();
+    for animation in animations.iter() {
+        animation.unchecked_into::<Animation>().cancel();
+    }
+}
+
+#[wasm_bindgen_test]
+pub async fn cancelled_leave_animation_still_finishes() {
+    let left = Rc::new(Cell::new(false));
+
+    let _ = create_scope({
+        let left = left.clone();
+        move |cx| {
+            let show = create_signal(cx, true);
+            let after_leave: Box<dyn Fn()> = Box::new(move || left.set(true));
+
+            let view = view! { cx,
+                Transition(
+                    show = show,
+                    animation = TransitionAnimation::fade().duration(10_000.0),
+                    after_leave = after_leave,
+                    class = "animated",
+                ) { "Content" }
+            };
+
+            sycamore::render_to(|_| view, &test_container());
+
+            show.set(false);
+        }
+    });
+
+    cancel_animations(&find(".animated").unwrap());
+    TimeoutFuture::new(0).await;
+
+    assert!(left.get());
+    assert!(find(".animated").is_none());
+}
+
+fn group_items() -> Vec<Element> {
+    let nodes = document().query_selector_all(".item").unwrap();
+    (0..nodes.length())
+        .map(|i| nodes.get(i).unwrap().unchecked_into())
+        .collect()
+}
+
+#[wasm_bindgen_test]
+pub async fn group_items_enter_leave_and_move() {
+    let items = create_rc_signal(vec![1, 2]);
+
+    let _ = create_scope({
+        let items = items.clone();
+        move |cx| {
+            let items = create_memo(cx, move || (*items.get()).clone());
+
+            let view = view! { cx,
+                TransitionGroup(
+                    iterable = items,
+                    view = |cx, item| view! { cx, div(class = "item") { (item) } },
+                    key = |item| *item,
+                    enter = "enter",
+                    enter_from = "enter-from",
+                    enter_to = "enter-to",
+                    leave = "leave",
+                    leave_from = "leave-from",
+                    leave_to = "leave-to",
+                    move_class = "move",
+                )
+            };
+
+            sycamore::render_to(|_| view, &test_container());
+        }
+    });
+
+    // Items present from the start only enter with `appear`.
+    assert!(group_items()
+        .iter()
+        .all(|item| !item.class_list().contains("enter")));
+
+    items.set(vec![3, 1, 2]);
+
+    let rendered = group_items();
+    assert_eq!(rendered.len(), 3);
+    // Everything below the new item is pushed down and plays a move.
+    assert!(rendered[1].class_list().contains("move"));
+    assert!(rendered[2].class_list().contains("move"));
+
+    TimeoutFuture::new(50).await;
+    let entered = &rendered[0];
+    assert!(entered.class_list().contains("enter"));
+    assert!(entered.class_list().contains("enter-to"));
+    assert!(!entered.class_list().contains("enter-from"));
+
+    end_transition(entered);
+    assert!(!entered.class_list().contains("enter"));
+
+    items.set(vec![3, 2]);
+
+    let leaving = &group_items()[1];
+    assert_eq!(group_items().len(), 3);
+    assert!(leaving.class_list().contains("leave"));
+
+    end_transition(leaving);
+    assert_eq!(group_items().len(), 2);
+}